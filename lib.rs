@@ -1,15 +1,27 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, String, Symbol, Vec};
+
+// Largest number of users accepted by a single batch call
+const MAX_VERIFY_BATCH: u32 = 100;
+
+// Ceiling for reputation reached through adjust_reputation
+const MAX_REPUTATION: u32 = 1000;
+
+// Number of entries kept in each user's recent activity log
+const MAX_RECENT_ACTIVITY: u32 = 50;
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Admin,
+    PendingAdmin,
     UserProfile(Address),
     UserRole(Address),
     UserReputation(Address),
     UserStatus(Address),
     UserActivity(Address),
+    RecentActivity(Address),
+    AdminList,
 }
 
 #[contracttype]
@@ -43,6 +55,46 @@ impl UserManagement {
         // Admin also gets the Admin role
         env.storage().persistent().set(&DataKey::UserRole(admin.clone()), &UserRole::Admin);
         env.storage().persistent().set(&DataKey::UserStatus(admin.clone()), &true);
+        Self::track_admin_role(&env, &admin, &UserRole::Admin);
+    }
+
+    // Admin: Propose a new instance admin. The transfer completes only once the new
+    // admin accepts; until then a fresh proposal replaces the pending one.
+    pub fn propose_admin(env: Env, current_admin: Address, new_admin: Address) {
+        current_admin.require_auth();
+
+        let instance_admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        if current_admin != instance_admin {
+            panic!("Not authorized: instance admin required");
+        }
+
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+        env.events().publish((symbol_short!("ADM_PROP"), current_admin), new_admin);
+    }
+
+    // Accept a pending admin proposal, becoming the instance admin. The previous admin
+    // keeps its Admin role until it is changed with set_role.
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        new_admin.require_auth();
+
+        let pending: Address = env.storage().instance().get(&DataKey::PendingAdmin).expect("No pending admin");
+        if new_admin != pending {
+            panic!("Not the pending admin");
+        }
+        let previous: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        env.storage().persistent().set(&DataKey::UserRole(new_admin.clone()), &UserRole::Admin);
+        env.storage().persistent().set(&DataKey::UserStatus(new_admin.clone()), &true);
+        Self::track_admin_role(&env, &new_admin, &UserRole::Admin);
+
+        env.events().publish((symbol_short!("ADM_ACPT"), new_admin), previous);
+    }
+
+    // Get the admin awaiting acceptance, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
     }
 
     // Register a new user
@@ -68,6 +120,8 @@ impl UserManagement {
         env.storage().persistent().set(&DataKey::UserReputation(user.clone()), &0u32);
         // Initialize activity count
         env.storage().persistent().set(&DataKey::UserActivity(user.clone()), &0u64);
+
+        env.events().publish((symbol_short!("USER_REG"), user), profile.profile_hash);
     }
 
     // Update user profile
@@ -86,6 +140,15 @@ impl UserManagement {
         env.storage().persistent().get(&DataKey::UserProfile(user)).expect("User not found")
     }
 
+    // Check if user is verified (false for unregistered users)
+    pub fn is_verified(env: Env, user: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get::<DataKey, UserProfile>(&DataKey::UserProfile(user))
+            .map(|profile| profile.is_verified)
+            .unwrap_or(false)
+    }
+
     // Admin: Verify user
     pub fn verify_user(env: Env, admin: Address, user: Address) {
         admin.require_auth();
@@ -93,7 +156,37 @@ impl UserManagement {
         
         let mut profile: UserProfile = env.storage().persistent().get(&DataKey::UserProfile(user.clone())).expect("User not found");
         profile.is_verified = true;
-        env.storage().persistent().set(&DataKey::UserProfile(user), &profile);
+        env.storage().persistent().set(&DataKey::UserProfile(user.clone()), &profile);
+
+        env.events().publish((symbol_short!("USER_VER"), user), admin);
+    }
+
+    // Admin: Verify many users at once. Unregistered addresses are skipped and returned
+    // instead of failing the whole batch.
+    pub fn verify_users_batch(env: Env, admin: Address, users: Vec<Address>) -> Vec<Address> {
+        admin.require_auth();
+        Self::check_admin(&env, &admin);
+
+        if users.len() > MAX_VERIFY_BATCH {
+            panic!("Batch too large");
+        }
+
+        let mut skipped = Vec::new(&env);
+        let mut verified = 0u32;
+        for user in users.iter() {
+            let key = DataKey::UserProfile(user.clone());
+            match env.storage().persistent().get::<DataKey, UserProfile>(&key) {
+                Some(mut profile) => {
+                    profile.is_verified = true;
+                    env.storage().persistent().set(&key, &profile);
+                    verified += 1;
+                }
+                None => skipped.push_back(user),
+            }
+        }
+
+        env.events().publish((symbol_short!("VERIFIED"), admin), (verified, skipped.len()));
+        skipped
     }
 
     // Admin: Set user role
@@ -101,7 +194,10 @@ impl UserManagement {
         admin.require_auth();
         Self::check_admin(&env, &admin);
         
-        env.storage().persistent().set(&DataKey::UserRole(user), &role);
+        Self::track_admin_role(&env, &user, &role);
+        env.storage().persistent().set(&DataKey::UserRole(user.clone()), &role);
+
+        env.events().publish((symbol_short!("ROLE_SET"), user), role);
     }
 
     // Get user role
@@ -114,7 +210,25 @@ impl UserManagement {
         admin.require_auth();
         Self::check_admin(&env, &admin);
         
-        env.storage().persistent().set(&DataKey::UserReputation(user), &score);
+        env.storage().persistent().set(&DataKey::UserReputation(user.clone()), &score);
+
+        env.events().publish((symbol_short!("REP_SET"), user), score);
+    }
+
+    // Admin: Change user reputation by a signed amount, clamped to 0..=MAX_REPUTATION
+    pub fn adjust_reputation(env: Env, admin: Address, user: Address, delta: i32) {
+        admin.require_auth();
+        Self::check_admin(&env, &admin);
+
+        let current = Self::get_reputation(env.clone(), user.clone());
+        let updated = if delta < 0 {
+            current.saturating_sub(delta.unsigned_abs())
+        } else {
+            current.saturating_add(delta as u32).min(MAX_REPUTATION)
+        };
+        env.storage().persistent().set(&DataKey::UserReputation(user.clone()), &updated);
+
+        env.events().publish((symbol_short!("REP_ADJ"), user), (current, updated));
     }
 
     // Get user reputation
@@ -126,8 +240,11 @@ impl UserManagement {
     pub fn suspend_user(env: Env, admin: Address, user: Address) {
         admin.require_auth();
         Self::check_admin(&env, &admin);
+        Self::check_not_last_admin(&env, &user);
         
-        env.storage().persistent().set(&DataKey::UserStatus(user), &false);
+        env.storage().persistent().set(&DataKey::UserStatus(user.clone()), &false);
+
+        env.events().publish((symbol_short!("SUSPENDED"), user), admin);
     }
 
     // Admin: Unsuspend user
@@ -135,7 +252,9 @@ impl UserManagement {
         admin.require_auth();
         Self::check_admin(&env, &admin);
         
-        env.storage().persistent().set(&DataKey::UserStatus(user), &true);
+        env.storage().persistent().set(&DataKey::UserStatus(user.clone()), &true);
+
+        env.events().publish((symbol_short!("UNSUSPEND"), user), admin);
     }
 
     // Check if user is active
@@ -143,13 +262,27 @@ impl UserManagement {
         env.storage().persistent().get(&DataKey::UserStatus(user)).unwrap_or(false)
     }
 
-    // Log user activity (increment counter)
-    pub fn log_activity(env: Env, user: Address) {
+    // Log user activity: increment the counter and append to the recent activity log,
+    // dropping the oldest entry once MAX_RECENT_ACTIVITY are kept
+    pub fn log_activity(env: Env, user: Address, activity_type: Symbol) {
         user.require_auth();
         Self::check_active(&env, &user);
 
         let count: u64 = env.storage().persistent().get(&DataKey::UserActivity(user.clone())).unwrap_or(0);
-        env.storage().persistent().set(&DataKey::UserActivity(user), &(count + 1));
+        env.storage().persistent().set(&DataKey::UserActivity(user.clone()), &(count + 1));
+
+        let key = DataKey::RecentActivity(user);
+        let mut recent: Vec<(Symbol, u64)> = env.storage().persistent().get(&key).unwrap_or_else(|| Vec::new(&env));
+        if recent.len() >= MAX_RECENT_ACTIVITY {
+            recent.pop_front();
+        }
+        recent.push_back((activity_type, env.ledger().timestamp()));
+        env.storage().persistent().set(&key, &recent);
+    }
+
+    // Most recent activity as (activity_type, timestamp), oldest first
+    pub fn get_recent_activity(env: Env, user: Address) -> Vec<(Symbol, u64)> {
+        env.storage().persistent().get(&DataKey::RecentActivity(user)).unwrap_or_else(|| Vec::new(&env))
     }
 
     pub fn get_activity_count(env: Env, user: Address) -> u64 {
@@ -171,6 +304,43 @@ impl UserManagement {
         }
     }
 
+    // Keep the list of role-admins in sync with role changes
+    fn track_admin_role(env: &Env, user: &Address, role: &UserRole) {
+        let mut admins: Vec<Address> = env.storage().instance().get(&DataKey::AdminList).unwrap_or_else(|| Vec::new(env));
+        let index = admins.first_index_of(user.clone());
+        match (role, index) {
+            (UserRole::Admin, None) => admins.push_back(user.clone()),
+            (UserRole::Admin, Some(_)) => return,
+            (_, Some(i)) => { admins.remove(i); }
+            (_, None) => return,
+        }
+        env.storage().instance().set(&DataKey::AdminList, &admins);
+    }
+
+    // The instance admin can never be suspended, and neither can the last active role-admin
+    fn check_not_last_admin(env: &Env, user: &Address) {
+        let instance_admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+        if user == &instance_admin {
+            panic!("Cannot suspend last admin");
+        }
+
+        let role: UserRole = env.storage().persistent().get(&DataKey::UserRole(user.clone())).unwrap_or(UserRole::None);
+        if role != UserRole::Admin {
+            return;
+        }
+
+        let admins: Vec<Address> = env.storage().instance().get(&DataKey::AdminList).unwrap_or_else(|| Vec::new(env));
+        let mut other_active = 0u32;
+        for other in admins.iter() {
+            if &other != user && Self::is_active(env.clone(), other) {
+                other_active += 1;
+            }
+        }
+        if other_active == 0 {
+            panic!("Cannot suspend last admin");
+        }
+    }
+
     fn check_active(env: &Env, user: &Address) {
         let is_active: bool = env.storage().persistent().get(&DataKey::UserStatus(user.clone())).unwrap_or(false);
         if !is_active {
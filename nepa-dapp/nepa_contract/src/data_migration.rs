@@ -1,4 +1,8 @@
-use soroban_sdk::{contract, contractimpl, Address, Env, Symbol, Map, Vec};
+use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol, Map, Vec};
+use crate::multi_utility::{
+    UtilityConfig, UtilityFee, UtilityMeter, UtilityProvider, UTILITY_CONFIGS, UTILITY_FEES,
+    UTILITY_METERS, UTILITY_PROVIDERS,
+};
 
 #[derive(Clone)]
 pub struct MigrationScript {
@@ -8,6 +12,19 @@ pub struct MigrationScript {
     pub description: Symbol,
 }
 
+// Snapshot of the multi-utility registries taken by backup_data. The whole snapshot is a
+// single persistent entry, so it only fits while the four maps together stay under the
+// network's ledger entry size limit (64 KiB at the time of writing); beyond that the
+// backup write fails and the registries would need to be backed up in chunks.
+#[derive(Clone)]
+pub struct MultiUtilityBackup {
+    pub providers: Map<String, UtilityProvider>,
+    pub configs: Map<String, UtilityConfig>,
+    pub meters: Map<String, UtilityMeter>,
+    pub fees: Map<String, UtilityFee>,
+    pub created_at: u64,
+}
+
 #[contract]
 pub struct DataMigration;
 
@@ -93,6 +110,24 @@ impl DataMigration {
             .unwrap_or_else(|| Vec::new(&env))
     }
 
+    /// Resolve a chain of registered scripts leading from `from_version` to `to_version`,
+    /// in execution order. Empty when no complete chain exists.
+    pub fn get_migration_path(env: Env, from_version: u32, to_version: u32) -> Vec<MigrationScript> {
+        let migrations: Map<u32, Vec<MigrationScript>> = env.storage()
+            .instance()
+            .get(&Symbol::short("MIGRATIONS"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut path = Vec::new(&env);
+        if from_version < to_version
+            && Self::find_path_to(&migrations, from_version, to_version, &mut path)
+        {
+            path
+        } else {
+            Vec::new(&env)
+        }
+    }
+
     /// Execute migration for a specific upgrade path
     pub fn execute_migration(
         env: Env,
@@ -158,12 +193,18 @@ impl DataMigration {
         let backup_timestamp = env.ledger().timestamp();
         let backup_id = Symbol::short(&format!("BACKUP_{}", backup_timestamp));
 
-        // In a real implementation, you would:
-        // 1. Copy all persistent storage data
-        // 2. Store it with backup_id
-        // 3. Return backup_id for restoration
+        // Snapshot the multi-utility registries; other billing state (balances, billing
+        // records, oracle feeds) is keyed per entry and isn't covered
+        let storage = env.storage().persistent();
+        let backup = MultiUtilityBackup {
+            providers: storage.get(&UTILITY_PROVIDERS).unwrap_or_else(|| Map::new(&env)),
+            configs: storage.get(&UTILITY_CONFIGS).unwrap_or_else(|| Map::new(&env)),
+            meters: storage.get(&UTILITY_METERS).unwrap_or_else(|| Map::new(&env)),
+            fees: storage.get(&UTILITY_FEES).unwrap_or_else(|| Map::new(&env)),
+            created_at: backup_timestamp,
+        };
+        storage.set(&(Symbol::short("BACKUP"), backup_id.clone()), &backup);
 
-        // For now, we'll emit a backup event
         env.events()
             .publish(
                 (Symbol::short("DATA_BACKUP"), backup_id),
@@ -185,12 +226,18 @@ impl DataMigration {
             return Err(Symbol::short("UNAUTHORIZED"));
         }
 
-        // In a real implementation, you would:
-        // 1. Load backup data using backup_id
-        // 2. Restore all persistent storage data
-        // 3. Verify restoration success
+        let backup: MultiUtilityBackup = env.storage()
+            .persistent()
+            .get(&(Symbol::short("BACKUP"), backup_id.clone()))
+            .ok_or(Symbol::short("NO_BACKUP"))?;
+
+        // Write the registries back wholesale, dropping anything added since the backup
+        let storage = env.storage().persistent();
+        storage.set(&UTILITY_PROVIDERS, &backup.providers);
+        storage.set(&UTILITY_CONFIGS, &backup.configs);
+        storage.set(&UTILITY_METERS, &backup.meters);
+        storage.set(&UTILITY_FEES, &backup.fees);
 
-        // For now, we'll emit a restore event
         env.events()
             .publish(
                 (Symbol::short("DATA_RESTORE"), backup_id),
@@ -207,4 +254,32 @@ impl DataMigration {
             .get(&Symbol::short("ADMIN"))
             .unwrap()
     }
+
+    /// Depth-first search backwards from `current`; on success `path` holds the chain
+    /// from `from_version` up to `current`. Every hop strictly lowers the version, so it ends.
+    fn find_path_to(
+        migrations: &Map<u32, Vec<MigrationScript>>,
+        from_version: u32,
+        current: u32,
+        path: &mut Vec<MigrationScript>,
+    ) -> bool {
+        let scripts = match migrations.get(current) {
+            Some(scripts) => scripts,
+            None => return false,
+        };
+
+        for script in scripts.iter() {
+            if script.from_version < from_version || script.from_version >= current {
+                continue;
+            }
+            if script.from_version == from_version
+                || Self::find_path_to(migrations, from_version, script.from_version, path)
+            {
+                path.push_back(script);
+                return true;
+            }
+        }
+
+        false
+    }
 }
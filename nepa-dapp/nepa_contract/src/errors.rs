@@ -0,0 +1,84 @@
+use soroban_sdk::contracterror;
+
+// Error codes returned by the billing and multi-utility entry points.
+// Codes are part of the public interface: never renumber, only append.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum BillingError {
+    NotInitialized = 1,
+    Unauthorized = 2,
+    InvalidUtilityType = 3,
+    InvalidFeeType = 4,
+    ProviderAlreadyRegistered = 5,
+    NoProvidersRegistered = 6,
+    ProviderNotFound = 7,
+    ProviderNotActive = 8,
+    UtilityTypeMismatch = 9,
+    UnauthorizedProvider = 10,
+    MeterAlreadyRegistered = 11,
+    MeterNotFound = 12,
+    MeterNotActive = 13,
+    ConfigNotFound = 14,
+    ConfigNotActive = 15,
+    RateUnavailable = 16,
+    ExchangeRateUnavailable = 17,
+    OracleNotInitialized = 18,
+    ReliabilityTooLow = 19,
+    AmountTooLow = 20,
+    AmountTooHigh = 21,
+    NotMeterCustomer = 22,
+    InvalidAmount = 23,
+    AutopayNotConfigured = 24,
+    NotOwnerOrKeeper = 25,
+    UtilityTypesNotInitialized = 26,
+    BatchTooLarge = 27,
+    ReadingRollback = 28,
+    StaleReading = 29,
+    FeeNotFound = 30,
+    InvalidCyclePeriod = 31,
+    SpendingCapExceeded = 32,
+    InvalidDecimals = 33,
+    DecimalsMismatch = 34,
+    CurrencyNotSet = 35,
+    InvalidRating = 36,
+    CurrencyChangeRequiresMigration = 37,
+    InvalidRange = 38,
+    InsufficientEarnings = 39,
+    ReconnectionCooldownActive = 40,
+    BillingRecordNotFound = 41,
+    RefundExceedsRecord = 42,
+    MeterNotesFull = 43,
+    InvalidVolumeDiscount = 44,
+    DisputeNotFound = 45,
+    DisputeAlreadyOpen = 46,
+    DisputeAlreadyResolved = 47,
+    InsufficientRefundPool = 48,
+    EscrowNotFound = 49,
+    EscrowAlreadySettled = 50,
+    EscrowTimeoutNotElapsed = 51,
+    InvalidEscrowRecipient = 52,
+    InvalidPromo = 53,
+    PromoNotApplicable = 54,
+    ProviderRegionMismatch = 55,
+    NotOverdue = 56,
+    PenaltyAlreadyApplied = 57,
+    InvalidDataCap = 58,
+    UserManagementNotLinked = 59,
+    InsufficientTender = 60,
+    InsufficientPrepaidBalance = 61,
+    ChargingSessionActive = 62,
+    ChargingSessionNotFound = 63,
+    ContractPaused = 64,
+    ConversionOverflow = 65,
+    NotProviderCustomer = 66,
+    AlreadyRatedThisCycle = 67,
+    ProviderHasActiveMeters = 68,
+    InvalidTierRange = 69,
+    OverlappingTiers = 70,
+    TierGap = 71,
+    InvalidTimeOfUseRate = 72,
+    InvalidTaxRate = 73,
+    InvalidDiscountRate = 74,
+    AutopayNotDue = 75,
+}
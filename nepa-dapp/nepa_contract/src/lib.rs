@@ -1,14 +1,21 @@
 #![no_std]
 // We added 'Address' and 'token' to imports
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, String, Symbol};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, panic_with_error, symbol_short, token, vec, Address, Env,
+    IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
+};
+
+mod errors;
+pub use errors::BillingError;
 
 mod oracle;
-use oracle::{OracleConfig, OracleManager, PriceFeed, UtilityRate};
+use oracle::{safe_convert, OracleConfig, OracleManager, PriceFeed, UtilityRate};
 
 mod multi_utility;
 use multi_utility::{
-    DiscountRate, FeeType, LateFeeConfig, MultiUtilityManager, SeasonalAdjustment, TaxRate,
-    TierRate, TimeOfUseRate, UtilityConfig, UtilityFee, UtilityMeter, UtilityProvider, UtilityType,
+    DiscountRate, FeeType, LateFeeConfig, MeterNote, MultiUtilityManager, ReadingResult, SeasonalAdjustment,
+    TaxRate, TierMode, TierRate, TimeOfUseRate, UtilityConfig, UtilityFee, UtilityMeter, UtilityProvider,
+    UtilityType, UtilityTypeDetails, UtilityVersion,
 };
 
 mod upgrade_proxy;
@@ -18,7 +25,7 @@ mod version_manager;
 use version_manager::{VersionManager, ContractVersion};
 
 mod data_migration;
-use data_migration::DataMigration;
+use data_migration::{DataMigration, MigrationScript};
 
 #[cfg(test)]
 mod tests;
@@ -26,6 +33,157 @@ mod tests;
 #[cfg(test)]
 mod upgrade_tests;
 
+// Storage keys for billing contract settings
+const BILLING_ADMIN: Symbol = symbol_short!("BILL_ADM");
+const KEEPERS: Symbol = symbol_short!("KEEPERS");
+const DEFAULT_CURRENCY: Symbol = symbol_short!("DEF_CUR");
+const PROVIDER_OWED: Symbol = symbol_short!("PROV_OWED");
+const TOTAL_OWED: Symbol = symbol_short!("TOT_OWED");
+const REFUND_POOLS: Symbol = symbol_short!("RFND_POOL");
+const PROVIDER_DISPUTES: Symbol = symbol_short!("PROV_DISP");
+const ESCROWS: Symbol = symbol_short!("ESCROWS");
+const ESCROW_SEQ: Symbol = symbol_short!("ESC_SEQ");
+const PROMOS: Symbol = symbol_short!("PROMOS");
+const PROMO_USED: Symbol = symbol_short!("PROMO_USE");
+const PROMO_STRICT: Symbol = symbol_short!("PROMO_STR");
+const DISCOUNT_ELIGIBILITY: Symbol = symbol_short!("DISC_ELIG");
+const SOLAR_CREDITS: Symbol = symbol_short!("SOLAR_CR");
+const STORAGE_TTLS: Symbol = symbol_short!("STOR_TTL");
+const USER_MGMT_CONTRACT: Symbol = symbol_short!("USER_MGMT");
+const ORACLE_CONTRACT: Symbol = symbol_short!("ORACLE");
+const REPUTATION_POLICY: Symbol = symbol_short!("REP_POL");
+const PAUSED: Symbol = symbol_short!("PAUSED");
+const AGGREGATED_PRICING: Symbol = symbol_short!("AGG_PRICE");
+const TWAP_WINDOW: Symbol = symbol_short!("TWAP_WIN");
+
+// Most entries returned by a single history range query
+const MAX_RANGE_RESULTS: u32 = 100;
+
+// Billing timestamps kept in a meter's history index; older records stay readable
+// through get_billing_details but drop out of history queries
+const MAX_BILLING_HISTORY: u32 = 500;
+
+// Most bills accepted in one pay_bills_batch call
+const MAX_PAYMENT_BATCH: u32 = 50;
+
+// Length of a spending-cap cycle, counted from the anchor set with the cap
+const SPENDING_CAP_CYCLE_SECS: u64 = 30 * 86400;
+
+// Minimum gap between two executions of the same autopay authorization
+const AUTOPAY_CYCLE_SECS: u64 = 30 * 86400;
+
+// Overdue days a compounding late fee accrues for; bounds the work done per quote
+const MAX_COMPOUND_LATE_FEE_DAYS: u64 = 365;
+
+// Everything about a customer's standing, for support tooling. The user fields come
+// from UserManagement and are only meaningful when `user_data_available` is true.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountSummary {
+    pub customer: Address,
+    pub user_data_available: bool,
+    pub role: u32,
+    pub is_active: bool,
+    pub reputation: u32,
+    pub is_verified: bool,
+    pub meters: Vec<String>,
+    pub total_paid: i128,
+    pub credit: i128,
+}
+
+// A customer's claim against one multi-utility billing record
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BillingDispute {
+    pub customer: Address,
+    pub amount: i128,
+    pub filed_at: u64,
+    pub resolved: bool,
+    pub upheld: bool,
+}
+
+// Tokens held by the contract until the payer releases them to the payee
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Escrow {
+    pub payer: Address,
+    pub payee: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub created_at: u64,
+    pub timeout_at: u64,
+    pub settled: bool,
+}
+
+// How pay_bills_batch treats a meter that can't be billed
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BatchPolicy {
+    AllOrNothing,
+    BestEffort,
+}
+
+// Per-meter outcome of pay_bills_batch. `amount` is what the payer was charged for the
+// meter after its credit and prepaid balance; error_code is the BillingError of a skipped meter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchPaymentResult {
+    pub meter_id: String,
+    pub paid: bool,
+    pub amount: i128,
+    pub error_code: u32,
+}
+
+// Percentage-off code redeemable once per customer until it expires or runs out
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PromoCode {
+    pub discount_bps: u32,
+    pub remaining_uses: u32,
+    pub expires_at: u64,
+}
+
+// Amounts making up a multi-utility bill before it is settled. `final_amount` is what
+// the customer pays in `paid_currency`, after any promo discount; `settled_amount` is
+// the same bill in the provider's `settled_currency`. `paid = settled * exchange_rate
+// / 10^rate_decimals`, with a rate of 1 and no decimals when no conversion applies.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BillQuote {
+    pub base_amount: i128,
+    pub overage_amount: i128,
+    pub discount_amount: i128,
+    pub tax_amount: i128,
+    pub late_fee_amount: i128,
+    pub export_credit: i128,
+    pub fee_amount: i128,
+    pub promo_discount: i128,
+    pub final_amount: i128,
+    pub config_version: u32,
+    pub paid_currency: String,
+    pub settled_amount: i128,
+    pub settled_currency: String,
+    pub exchange_rate: i128,
+    pub rate_decimals: u32,
+}
+
+// What a multi-utility payment cost the customer in their currency and what it is
+// worth in the provider's, with the exchange rate that links them
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentReceipt {
+    pub meter_id: String,
+    pub base_amount: i128,
+    pub overage_amount: i128,
+    pub discount_amount: i128,
+    pub original_amount: i128,
+    pub original_currency: String,
+    pub settled_amount: i128,
+    pub settled_currency: String,
+    pub exchange_rate: i128,
+    pub rate_decimals: u32,
+}
+
 #[contract]
 pub struct NepaBillingContract;
 
@@ -33,10 +191,76 @@ pub struct NepaBillingContract;
 impl NepaBillingContract {
     // Initialize the contract with oracle support
     pub fn initialize(env: Env, admin: Address, oracle_config: OracleConfig) {
+        // Record the billing admin
+        env.storage().instance().set(&BILLING_ADMIN, &admin);
+
         // Initialize oracle manager
         OracleManager::initialize_oracle(env, admin, oracle_config);
     }
 
+    // Which subsystems this deployment has set up:
+    // (oracle, multi-utility, upgrade, UserManagement contract linked)
+    pub fn get_init_status(env: Env) -> (bool, bool, bool, bool) {
+        (
+            OracleManager::is_initialized(env.clone()),
+            MultiUtilityManager::is_initialized(env.clone()),
+            UpgradeProxy::is_initialized(env.clone()),
+            env.storage().instance().has(&USER_MGMT_CONTRACT),
+        )
+    }
+
+    // === CIRCUIT BREAKER ===
+
+    // Halt every payment path until unpaused. Reads stay available while paused.
+    pub fn pause(env: Env, admin: Address) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&PAUSED, &true);
+        env.events().publish((symbol_short!("PAUSED"), admin), true);
+        Ok(())
+    }
+
+    pub fn unpause(env: Env, admin: Address) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&PAUSED, &false);
+        env.events().publish((symbol_short!("PAUSED"), admin), false);
+        Ok(())
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&PAUSED).unwrap_or(false)
+    }
+
+    // === LINKED CONTRACTS ===
+
+    // Point the contract at an oracle deployment; once linked, only it may push price updates
+    pub fn set_oracle_contract(env: Env, admin: Address, oracle: Address) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&ORACLE_CONTRACT, &oracle);
+        env.events().publish((symbol_short!("LINKED"), symbol_short!("ORACLE")), oracle);
+        Ok(())
+    }
+
+    pub fn get_oracle_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&ORACLE_CONTRACT)
+    }
+
+    // Point the contract at a UserManagement deployment, used for activity logging,
+    // reputation and account summaries
+    pub fn set_user_management_contract(
+        env: Env,
+        admin: Address,
+        user_mgmt: Address,
+    ) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&USER_MGMT_CONTRACT, &user_mgmt);
+        env.events().publish((symbol_short!("LINKED"), symbol_short!("USER_MGMT")), user_mgmt);
+        Ok(())
+    }
+
+    pub fn get_user_management_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&USER_MGMT_CONTRACT)
+    }
+
     // Enhanced pay_bill with oracle integration
     pub fn pay_bill_with_oracle(
         env: Env,
@@ -44,34 +268,52 @@ impl NepaBillingContract {
         token_address: Address,
         meter_id: String,
         amount: i128,
-        currency: String,
+        currency: Option<String>,
         use_exchange_rate: bool,
-    ) -> Result<(), String> {
+    ) -> Result<(), BillingError> {
+        Self::when_not_paused(&env)?;
+
         // 1. Verify the user authorized this payment
         from.require_auth();
 
         // 2. Get exchange rate if needed
         let mut final_amount = amount;
         if use_exchange_rate {
+            let currency = Self::resolve_currency(&env, currency)?;
             let exchange_rate_id = format!("{}_USD", currency);
-            let price_feed = OracleManager::get_price_feed(env.clone(), exchange_rate_id)
-                .ok_or("Exchange rate not available")?;
+            let price_feed = Self::conversion_feed(&env, exchange_rate_id.clone())
+                .ok_or(BillingError::ExchangeRateUnavailable)?;
 
             // Validate price feed reliability
             let config: OracleConfig = env
                 .storage()
                 .instance()
                 .get(&symbol_short!("OR_CONF"))
-                .ok_or("Oracle not initialized")?;
+                .ok_or(BillingError::OracleNotInitialized)?;
 
-            if price_feed.reliability_score < config.min_reliability_score {
-                return Err("Price feed reliability too low".to_string());
+            // Both the feed's configured score and its tracked call history must qualify
+            let tracked_score = OracleManager::get_reliability_score(env.clone(), exchange_rate_id.clone());
+            if price_feed.reliability_score < config.min_reliability_score
+                || tracked_score < config.min_reliability_score
+            {
+                return Err(BillingError::ReliabilityTooLow);
             }
 
-            // Convert amount using exchange rate (assuming price is in USD)
-            final_amount = (amount * price_feed.price) / (10_i128.pow(price_feed.decimals));
+            // Convert amount using exchange rate (assuming price is in USD), averaged over
+            // the TWAP window when one is set
+            let twap_window = Self::get_twap_window(env.clone());
+            let price = if twap_window > 0 {
+                OracleManager::get_twap(env.clone(), exchange_rate_id, twap_window)
+                    .ok_or(BillingError::ExchangeRateUnavailable)?
+            } else {
+                price_feed.price
+            };
+            final_amount = safe_convert(amount, price, price_feed.decimals)
+                .map_err(|_| BillingError::ConversionOverflow)?;
         }
 
+        Self::apply_spending_cap(&env, &meter_id, final_amount)?;
+
         // 3. Initialize the Token client
         let token_client = token::Client::new(&env, &token_address);
 
@@ -79,10 +321,9 @@ impl NepaBillingContract {
         token_client.transfer(&from, &env.current_contract_address(), &final_amount);
 
         // 5. Update the meter record
-        let current_total: i128 = env.storage().persistent().get(&meter_id).unwrap_or(0);
-        env.storage()
-            .persistent()
-            .set(&meter_id, &(current_total + final_amount));
+        Self::add_to_total_paid(&env, &meter_id, final_amount);
+        Self::record_last_payment(&env, &meter_id, final_amount);
+        Self::publish_bill_paid(&env, &meter_id, final_amount, Self::meter_utility_type(&env, &meter_id));
 
         Ok(())
     }
@@ -96,40 +337,47 @@ impl NepaBillingContract {
         kwh_consumed: i128,
         utility_type: String,
         region: String,
-        currency: String,
-    ) -> Result<(), String> {
+        currency: Option<String>,
+    ) -> Result<(), BillingError> {
+        Self::when_not_paused(&env)?;
+
         // 1. Verify authorization
         from.require_auth();
 
         // 2. Get utility rate
         let rate_id = format!("{}_{}", utility_type, region);
         let utility_rate = OracleManager::get_utility_rate(env.clone(), rate_id)
-            .ok_or("Utility rate not available")?;
+            .ok_or(BillingError::RateUnavailable)?;
 
         // 3. Validate utility rate
         let config: OracleConfig = env
             .storage()
             .instance()
             .get(&symbol_short!("OR_CONF"))
-            .ok_or("Oracle not initialized")?;
+            .ok_or(BillingError::OracleNotInitialized)?;
 
         if utility_rate.reliability_score < config.min_reliability_score {
-            return Err("Utility rate reliability too low".to_string());
+            return Err(BillingError::ReliabilityTooLow);
         }
 
-        // 4. Calculate bill amount
-        let subtotal = kwh_consumed * utility_rate.rate_per_kwh;
+        // 4. Calculate bill amount, preferring the multi-source aggregate rate
+        let rate_per_kwh = OracleManager::get_aggregated_utility_rate(env.clone(), utility_type.clone(), region)
+            .unwrap_or(utility_rate.rate_per_kwh);
+        let subtotal = kwh_consumed
+            .checked_mul(rate_per_kwh)
+            .ok_or(BillingError::ConversionOverflow)?;
 
         // 5. Apply currency conversion if needed
+        let currency = Self::resolve_currency(&env, currency)?;
         let mut final_amount = subtotal;
         if utility_rate.currency != currency {
-            let exchange_rate_id = format!("{}_{}", utility_rate.currency, currency);
-            let price_feed = OracleManager::get_price_feed(env.clone(), exchange_rate_id)
-                .ok_or("Exchange rate not available")?;
-
-            final_amount = (subtotal * price_feed.price) / (10_i128.pow(price_feed.decimals));
+            let (rate, decimals) = Self::conversion_rate(&env, &utility_rate.currency, &currency)?;
+            final_amount = safe_convert(subtotal, rate, decimals)
+                .map_err(|_| BillingError::ConversionOverflow)?;
         }
 
+        Self::apply_spending_cap(&env, &meter_id, final_amount)?;
+
         // 6. Process payment
         let token_client = token::Client::new(&env, &token_address);
         token_client.transfer(&from, &env.current_contract_address(), &final_amount);
@@ -138,11 +386,15 @@ impl NepaBillingContract {
         let billing_key = format!("{}_{}", meter_id, env.ledger().timestamp());
         let billing_data = (
             kwh_consumed,
-            utility_rate.rate_per_kwh,
+            rate_per_kwh,
             final_amount,
-            utility_type,
+            utility_type.clone(),
         );
         env.storage().persistent().set(&billing_key, &billing_data);
+        Self::add_to_total_paid(&env, &meter_id, final_amount);
+        Self::record_last_payment(&env, &meter_id, final_amount);
+        MultiUtilityManager::record_consumption(&env, &meter_id, kwh_consumed);
+        Self::publish_bill_paid(&env, &meter_id, final_amount, utility_type);
 
         Ok(())
     }
@@ -155,9 +407,17 @@ impl NepaBillingContract {
         meter_id: String,
         amount: i128,
     ) {
+        if let Err(error) = Self::when_not_paused(&env) {
+            panic_with_error!(&env, error);
+        }
+
         // 1. Verify the user authorized this payment
         from.require_auth();
 
+        if let Err(error) = Self::apply_spending_cap(&env, &meter_id, amount) {
+            panic_with_error!(&env, error);
+        }
+
         // 2. Initialize the Token client (for XLM or USDC)
         let token_client = token::Client::new(&env, &token_address);
 
@@ -165,16 +425,32 @@ impl NepaBillingContract {
         token_client.transfer(&from, &env.current_contract_address(), &amount);
 
         // 4. Update the meter record (using i128 for larger money values)
-        let current_total: i128 = env.storage().persistent().get(&meter_id).unwrap_or(0);
-        env.storage()
-            .persistent()
-            .set(&meter_id, &(current_total + amount));
+        Self::add_to_total_paid(&env, &meter_id, amount);
+        Self::record_last_payment(&env, &meter_id, amount);
+        Self::publish_bill_paid(&env, &meter_id, amount, Self::meter_utility_type(&env, &meter_id));
+    }
+
+    // Currency used by payment functions when the caller omits one
+    pub fn set_default_currency(env: Env, admin: Address, currency: String) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&DEFAULT_CURRENCY, &currency);
+        Ok(())
+    }
+
+    pub fn get_default_currency(env: Env) -> Option<String> {
+        env.storage().instance().get(&DEFAULT_CURRENCY)
     }
 
     pub fn get_total_paid(env: Env, meter_id: String) -> i128 {
         env.storage().persistent().get(&meter_id).unwrap_or(0)
     }
 
+    // Timestamp and amount of the most recent successful payment for a meter
+    pub fn get_last_payment(env: Env, meter_id: String) -> Option<(u64, i128)> {
+        let last_payment_key = format!("{}_last_pay", meter_id);
+        env.storage().persistent().get(&last_payment_key)
+    }
+
     // Get billing details
     pub fn get_billing_details(
         env: Env,
@@ -185,454 +461,2912 @@ impl NepaBillingContract {
         env.storage().persistent().get(&billing_key)
     }
 
-    // Oracle management functions (delegated to OracleManager)
-    pub fn add_price_feed(env: Env, admin: Address, feed_id: String, price_feed: PriceFeed) {
-        OracleManager::add_price_feed(env, admin, feed_id, price_feed);
-    }
+    // Estimate how many days a prepaid meter's balance will last at its average daily spend
+    pub fn estimate_prepaid_days_remaining(env: Env, meter_id: String) -> Option<u32> {
+        let balance = Self::read_prepaid_balance(&env, &meter_id);
+        let history = Self::read_billing_history(&env, &meter_id);
 
-    pub fn update_price_feed(
-        env: Env,
-        feed_id: String,
-        new_price: i128,
-        timestamp: u64,
-    ) -> Result<(), String> {
-        OracleManager::update_price_feed(env, feed_id, new_price, timestamp)
-    }
+        // Need at least two bills to establish a spending rate
+        if history.len() < 2 {
+            return None;
+        }
 
-    pub fn get_price_feed(env: Env, feed_id: String) -> Option<PriceFeed> {
-        OracleManager::get_price_feed(env, feed_id)
-    }
+        let first_timestamp = history.get(0)?;
+        let elapsed_days = (env.ledger().timestamp() - first_timestamp) / 86400;
+        if elapsed_days == 0 {
+            return None;
+        }
 
-    pub fn add_utility_rate(env: Env, admin: Address, rate_id: String, utility_rate: UtilityRate) {
-        OracleManager::add_utility_rate(env, admin, rate_id, utility_rate);
+        let mut total_spent = 0i128;
+        for timestamp in history.iter() {
+            total_spent += Self::read_billed_amount(&env, &meter_id, timestamp).unwrap_or(0);
+        }
+
+        let average_daily_spend = total_spent / elapsed_days as i128;
+        if average_daily_spend <= 0 {
+            return None;
+        }
+
+        Some((balance.max(0) / average_daily_spend) as u32)
     }
 
-    pub fn update_utility_rate(
+    // Multi-utility payments (timestamp, final amount) with from_ts <= timestamp <= to_ts,
+    // oldest first. At most `limit` entries are returned, capped at MAX_RANGE_RESULTS.
+    pub fn get_payments_in_range(
         env: Env,
-        rate_id: String,
-        new_rate: i128,
-        timestamp: u64,
-    ) -> Result<(), String> {
-        OracleManager::update_utility_rate(env, rate_id, new_rate, timestamp)
-    }
+        meter_id: String,
+        from_ts: u64,
+        to_ts: u64,
+        limit: u32,
+    ) -> Result<Vec<(u64, i128)>, BillingError> {
+        if from_ts > to_ts {
+            return Err(BillingError::InvalidRange);
+        }
 
-    pub fn get_utility_rate(env: Env, rate_id: String) -> Option<UtilityRate> {
-        OracleManager::get_utility_rate(env, rate_id)
-    }
+        let limit = limit.min(MAX_RANGE_RESULTS);
+        let mut payments = Vec::new(&env);
+        for timestamp in Self::read_billing_history(&env, &meter_id).iter() {
+            if payments.len() >= limit || timestamp > to_ts {
+                break;
+            }
+            if timestamp < from_ts {
+                continue;
+            }
+            if let Some(amount) = Self::read_billed_amount(&env, &meter_id, timestamp) {
+                payments.push_back((timestamp, amount));
+            }
+        }
 
-    pub fn get_oracle_stats(env: Env) -> (oracle::OracleCost, oracle::OracleReliability, u8) {
-        OracleManager::get_oracle_stats(env)
+        Ok(payments)
     }
 
-    pub fn should_update_oracles(env: Env) -> (bool, bool) {
-        (
-            OracleManager::should_update_price_feeds(env.clone()),
-            OracleManager::should_update_utility_rates(env),
-        )
+    // A page of a meter's multi-utility bills as (timestamp, final amount), newest first.
+    // `start` counts entries skipped from the newest; `limit` is capped at MAX_RANGE_RESULTS.
+    pub fn get_billing_history(env: Env, meter_id: String, start: u32, limit: u32) -> Vec<(u64, i128)> {
+        let history = Self::read_billing_history(&env, &meter_id);
+        let limit = limit.min(MAX_RANGE_RESULTS);
+        let mut page = Vec::new(&env);
+
+        let mut index = history.len().saturating_sub(start);
+        while index > 0 && page.len() < limit {
+            index -= 1;
+            let timestamp = history.get(index).unwrap();
+            if let Some(amount) = Self::read_billed_amount(&env, &meter_id, timestamp) {
+                page.push_back((timestamp, amount));
+            }
+        }
+
+        page
     }
 
-    // === MULTI-UTILITY FUNCTIONS ===
+    // === PROVIDER EARNINGS ===
 
-    // Initialize multi-utility system
-    pub fn initialize_multi_utility(env: Env, admin: Address) {
-        MultiUtilityManager::initialize(env, admin);
+    // Amount of `token_address` currently held by this contract
+    pub fn get_contract_balance(env: Env, token_address: Address) -> i128 {
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.balance(&env.current_contract_address())
     }
 
-    // Register utility provider
-    pub fn register_utility_provider(
-        env: Env,
-        admin: Address,
-        provider_id: String,
-        name: String,
-        provider_address: Address,
-        utility_type: u8,
-        region: String,
-        license_number: String,
-        contact_info: String,
-    ) -> Result<(), String> {
-        MultiUtilityManager::register_provider(
-            env,
-            admin,
-            provider_id,
-            name,
-            provider_address,
-            utility_type,
-            region,
-            license_number,
-            contact_info,
-        )
+    // Earnings credited to a provider and not yet withdrawn
+    pub fn get_provider_owed(env: Env, provider_id: String) -> i128 {
+        let owed: Map<String, i128> = env
+            .storage()
+            .persistent()
+            .get(&PROVIDER_OWED)
+            .unwrap_or_else(|| Map::new(&env));
+        owed.get(provider_id).unwrap_or(0)
     }
 
-    // Add utility configuration
-    pub fn add_utility_configuration(
-        env: Env,
-        admin: Address,
-        config_id: String,
-        utility_type: u8,
-        provider_id: String,
-        region: String,
-        base_rate: i128,
-        currency: String,
-        decimals: u32,
-        billing_cycle_days: u32,
-        grace_period_days: u32,
-        minimum_payment: i128,
-        maximum_payment: i128,
-    ) -> Result<(), String> {
-        MultiUtilityManager::add_utility_config(
-            env,
-            admin,
-            config_id,
-            utility_type,
-            provider_id,
-            region,
-            base_rate,
-            currency,
-            decimals,
-            billing_cycle_days,
-            grace_period_days,
-            minimum_payment,
-            maximum_payment,
-        )
+    // Sum of all providers' unwithdrawn earnings; a healthy contract holds at least this much
+    pub fn get_total_owed_to_providers(env: Env) -> i128 {
+        env.storage().persistent().get(&TOTAL_OWED).unwrap_or(0)
     }
 
-    // Register utility meter
-    pub fn register_utility_meter(
+    // Provider withdraws part of its earnings to its registered address
+    pub fn withdraw_provider_earnings(
         env: Env,
-        provider_address: Address,
-        meter_id: String,
-        utility_type: u8,
         provider_id: String,
-        customer_address: Address,
-        location: String,
-        meter_model: String,
-        firmware_version: String,
-        is_smart_meter: bool,
-    ) -> Result<(), String> {
-        MultiUtilityManager::register_meter(
-            env,
-            provider_address,
-            meter_id,
-            utility_type,
-            provider_id,
-            customer_address,
-            location,
-            meter_model,
-            firmware_version,
-            is_smart_meter,
-        )
-    }
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), BillingError> {
+        let provider = MultiUtilityManager::get_provider(env.clone(), provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        provider.address.require_auth();
 
-    // Add utility fee
-    pub fn add_utility_fee_structure(
-        env: Env,
-        admin: Address,
-        fee_id: String,
-        utility_type: u8,
-        provider_id: String,
-        fee_type: u8,
-        fee_amount: i128,
-        fee_percentage: Option<i128>,
-        is_percentage: bool,
-        description: String,
-    ) -> Result<(), String> {
-        MultiUtilityManager::add_utility_fee(
-            env,
-            admin,
-            fee_id,
-            utility_type,
-            provider_id,
-            fee_type,
-            fee_amount,
-            fee_percentage,
-            is_percentage,
-            description,
-        )
+        if amount <= 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+        if amount > Self::get_provider_owed(env.clone(), provider_id.clone()) {
+            return Err(BillingError::InsufficientEarnings);
+        }
+
+        Self::credit_provider(&env, &provider_id, -amount);
+
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&env.current_contract_address(), &provider.address, &amount);
+
+        Ok(())
     }
 
-    // Enhanced multi-utility payment function
-    pub fn pay_multi_utility_bill(
+    // Provider refunds part or all of one multi-utility billing record. The refund is
+    // taken from the provider's earnings and transferred back to the meter's customer;
+    // refunds against a record can never add up to more than it charged.
+    pub fn refund_billing_record(
         env: Env,
-        from: Address,
+        provider_address: Address,
         token_address: Address,
         meter_id: String,
-        consumption: i128,
-        currency: String,
-        apply_fees: bool,
-    ) -> Result<(), String> {
-        // 1. Verify authorization
-        from.require_auth();
-
-        // 2. Get meter information
+        timestamp: u64,
+        amount: i128,
+    ) -> Result<(), BillingError> {
+        let (provider_id, refunded) =
+            Self::check_record_refund(&env, &provider_address, &meter_id, timestamp, amount)?;
         let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
-            .ok_or("Meter not found")?;
+            .ok_or(BillingError::MeterNotFound)?;
 
-        if !meter.is_active {
-            return Err("Meter is not active".to_string());
-        }
+        Self::credit_provider(&env, &provider_id, -amount);
 
-        // 3. Get utility configuration
-        let config_id = format!("{}_{}", meter.provider_id, meter.region);
-        let config = MultiUtilityManager::get_utility_config(env.clone(), config_id)
-            .ok_or("Utility configuration not found")?;
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&env.current_contract_address(), &meter.customer_address, &amount);
+
+        let refund_key = format!("{}_{}_refund", meter_id, timestamp);
+        env.storage().persistent().set(&refund_key, &(refunded + amount));
+
+        env.events()
+            .publish((symbol_short!("REC_RFND"), meter_id), (timestamp, amount));
+
+        Ok(())
+    }
+
+    // Provider returns tokens for an overcharged or disputed billing record straight to
+    // `to`, out of the provider's earnings. Shares the per-record refund limit with
+    // refund_billing_record.
+    pub fn refund_payment(
+        env: Env,
+        provider_address: Address,
+        token_address: Address,
+        meter_id: String,
+        to: Address,
+        amount: i128,
+        original_timestamp: u64,
+    ) -> Result<(), BillingError> {
+        let (provider_id, refunded) =
+            Self::check_record_refund(&env, &provider_address, &meter_id, original_timestamp, amount)?;
+
+        Self::credit_provider(&env, &provider_id, -amount);
+
+        let refund_key = format!("{}_{}_refund", meter_id, original_timestamp);
+        env.storage().persistent().set(&refund_key, &(refunded + amount));
+
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
+
+        env.events()
+            .publish((symbol_short!("REFUNDED"), meter_id), (original_timestamp, to, amount));
+
+        Ok(())
+    }
+
+    // Returns (amount refunded so far, fully refunded) for a billing record
+    pub fn get_record_refund_status(env: Env, meter_id: String, timestamp: u64) -> (i128, bool) {
+        let refund_key = format!("{}_{}_refund", meter_id, timestamp);
+        let refunded: i128 = env.storage().persistent().get(&refund_key).unwrap_or(0);
+        let billed = Self::read_billed_amount(&env, &meter_id, timestamp).unwrap_or(0);
+        (refunded, refunded > 0 && refunded >= billed)
+    }
+
+    // === DISPUTES AND REFUND POOLS ===
+
+    // Provider deposits tokens the contract uses to pay out upheld disputes
+    pub fn fund_refund_pool(
+        env: Env,
+        provider_address: Address,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), BillingError> {
+        provider_address.require_auth();
+
+        let provider = MultiUtilityManager::get_provider_by_address(env.clone(), provider_address.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        if amount <= 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&provider_address, &env.current_contract_address(), &amount);
+
+        let pool = Self::get_refund_pool(env.clone(), provider.provider_id.clone(), token_address.clone());
+        Self::set_refund_pool(&env, &provider.provider_id, &token_address, pool + amount);
+
+        env.events()
+            .publish((symbol_short!("POOL_FUND"), provider.provider_id), (token_address, amount));
+
+        Ok(())
+    }
+
+    // Balance of a provider's refund pool in the given token
+    pub fn get_refund_pool(env: Env, provider_id: String, token_address: Address) -> i128 {
+        let pools: Map<(String, Address), i128> = env
+            .storage()
+            .persistent()
+            .get(&REFUND_POOLS)
+            .unwrap_or_else(|| Map::new(&env));
+        pools.get((provider_id, token_address)).unwrap_or(0)
+    }
+
+    // Customer disputes part or all of one of their billing records
+    pub fn file_dispute(
+        env: Env,
+        customer: Address,
+        meter_id: String,
+        timestamp: u64,
+        amount: i128,
+    ) -> Result<(), BillingError> {
+        customer.require_auth();
+
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        if meter.customer_address != customer {
+            return Err(BillingError::NotMeterCustomer);
+        }
+
+        let billed = Self::read_billed_amount(&env, &meter_id, timestamp)
+            .ok_or(BillingError::BillingRecordNotFound)?;
+        if amount <= 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+        let (refunded, _) = Self::get_record_refund_status(env.clone(), meter_id.clone(), timestamp);
+        if refunded + amount > billed {
+            return Err(BillingError::RefundExceedsRecord);
+        }
+
+        if let Some(existing) = Self::get_dispute(env.clone(), meter_id.clone(), timestamp) {
+            if !existing.resolved {
+                return Err(BillingError::DisputeAlreadyOpen);
+            }
+        }
+
+        let dispute = BillingDispute {
+            customer,
+            amount,
+            filed_at: env.ledger().timestamp(),
+            resolved: false,
+            upheld: false,
+        };
+        let dispute_key = format!("{}_{}_dispute", meter_id, timestamp);
+        env.storage().persistent().set(&dispute_key, &dispute);
+
+        let mut disputes: Map<String, u32> = env
+            .storage()
+            .persistent()
+            .get(&PROVIDER_DISPUTES)
+            .unwrap_or_else(|| Map::new(&env));
+        let filed = disputes.get(meter.provider_id.clone()).unwrap_or(0);
+        disputes.set(meter.provider_id, filed + 1);
+        env.storage().persistent().set(&PROVIDER_DISPUTES, &disputes);
+
+        env.events()
+            .publish((symbol_short!("DISPUTE"), meter_id), (timestamp, amount));
+
+        Ok(())
+    }
+
+    // Provider settles a dispute. Upheld disputes are refunded to the customer out of
+    // the provider's refund pool for `token_address`, which must cover the full payout.
+    // Refunds made since filing count against the record, so the payout is capped at
+    // what is still unrefunded.
+    pub fn resolve_dispute(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        timestamp: u64,
+        token_address: Address,
+        uphold: bool,
+    ) -> Result<(), BillingError> {
+        provider_address.require_auth();
+
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        let provider = MultiUtilityManager::get_provider(env.clone(), meter.provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        if provider.address != provider_address {
+            return Err(BillingError::UnauthorizedProvider);
+        }
+
+        let mut dispute = Self::get_dispute(env.clone(), meter_id.clone(), timestamp)
+            .ok_or(BillingError::DisputeNotFound)?;
+        if dispute.resolved {
+            return Err(BillingError::DisputeAlreadyResolved);
+        }
+
+        if uphold {
+            let billed = Self::read_billed_amount(&env, &meter_id, timestamp).unwrap_or(0);
+            let (refunded, _) = Self::get_record_refund_status(env.clone(), meter_id.clone(), timestamp);
+            let payout = dispute.amount.min(billed - refunded).max(0);
+
+            let pool = Self::get_refund_pool(env.clone(), provider.provider_id.clone(), token_address.clone());
+            if pool < payout {
+                return Err(BillingError::InsufficientRefundPool);
+            }
+
+            if payout > 0 {
+                Self::set_refund_pool(&env, &provider.provider_id, &token_address, pool - payout);
+
+                let token_client = token::Client::new(&env, &token_address);
+                token_client.transfer(&env.current_contract_address(), &dispute.customer, &payout);
+
+                let refund_key = format!("{}_{}_refund", meter_id, timestamp);
+                env.storage().persistent().set(&refund_key, &(refunded + payout));
+            }
+        }
+
+        dispute.resolved = true;
+        dispute.upheld = uphold;
+        let dispute_key = format!("{}_{}_dispute", meter_id, timestamp);
+        env.storage().persistent().set(&dispute_key, &dispute);
+
+        env.events()
+            .publish((symbol_short!("DISP_RES"), meter_id), (timestamp, uphold));
+
+        Ok(())
+    }
+
+    // Disputes filed against a provider per completed transaction, in basis points
+    pub fn get_provider_dispute_rate(env: Env, provider_id: String) -> u32 {
+        let transactions = MultiUtilityManager::get_provider(env.clone(), provider_id.clone())
+            .map(|provider| provider.total_transactions)
+            .unwrap_or(0);
+        if transactions == 0 {
+            return 0;
+        }
+
+        let disputes: Map<String, u32> = env
+            .storage()
+            .persistent()
+            .get(&PROVIDER_DISPUTES)
+            .unwrap_or_else(|| Map::new(&env));
+        let filed = disputes.get(provider_id).unwrap_or(0) as u64;
+
+        (filed * 10000 / transactions) as u32
+    }
+
+    // Dispute filed against a billing record, if any
+    pub fn get_dispute(env: Env, meter_id: String, timestamp: u64) -> Option<BillingDispute> {
+        let dispute_key = format!("{}_{}_dispute", meter_id, timestamp);
+        env.storage().persistent().get(&dispute_key)
+    }
+
+    // Total consumption and amount paid across a customer's meters for multi-utility bills
+    // with from_ts <= timestamp <= to_ts. Amounts are summed as recorded, in whatever
+    // currency each bill was paid; a customer without meters gets zeros.
+    pub fn get_customer_summary(env: Env, customer: Address, from_ts: u64, to_ts: u64) -> (i128, i128) {
+        let mut total_consumption = 0i128;
+        let mut total_paid = 0i128;
+
+        for meter in MultiUtilityManager::list_meters_by_customer(env.clone(), customer).iter() {
+            for timestamp in Self::read_billing_history(&env, &meter.meter_id).iter() {
+                if timestamp > to_ts {
+                    break;
+                }
+                if timestamp < from_ts {
+                    continue;
+                }
+                if let Some((consumption, amount)) = Self::read_billed_usage(&env, &meter.meter_id, timestamp) {
+                    total_consumption += consumption;
+                    total_paid += amount;
+                }
+            }
+        }
+
+        (total_consumption, total_paid)
+    }
+
+    // Assemble a customer's account summary. Billing data is always included; user data
+    // is read from the linked UserManagement and left at defaults if that call fails.
+    pub fn get_account_summary(env: Env, customer: Address) -> AccountSummary {
+        let meters = MultiUtilityManager::get_customer_meter_ids(env.clone(), customer.clone());
+
+        let mut total_paid = 0i128;
+        let mut credit = 0i128;
+        for meter_id in meters.iter() {
+            total_paid += Self::get_total_paid(env.clone(), meter_id.clone());
+            credit += Self::read_prepaid_balance(&env, &meter_id);
+            credit += Self::get_meter_credit(env.clone(), meter_id.clone());
+        }
+
+        let mut summary = AccountSummary {
+            customer: customer.clone(),
+            user_data_available: false,
+            role: 0,
+            is_active: false,
+            reputation: 0,
+            is_verified: false,
+            meters,
+            total_paid,
+            credit,
+        };
+
+        if let Some(user_mgmt) = Self::get_user_management_contract(env.clone()) {
+            let role = Self::try_read_user::<u32>(&env, &user_mgmt, "get_role", &customer);
+            let is_active = Self::try_read_user::<bool>(&env, &user_mgmt, "is_active", &customer);
+            let reputation = Self::try_read_user::<u32>(&env, &user_mgmt, "get_reputation", &customer);
+            let is_verified = Self::try_read_user::<bool>(&env, &user_mgmt, "is_verified", &customer);
+
+            if let (Some(role), Some(is_active), Some(reputation), Some(is_verified)) =
+                (role, is_active, reputation, is_verified)
+            {
+                summary.user_data_available = true;
+                summary.role = role;
+                summary.is_active = is_active;
+                summary.reputation = reputation;
+                summary.is_verified = is_verified;
+            }
+        }
+
+        summary
+    }
+
+    // === ESCROW ===
+
+    // Payer locks `amount` for `payee`; the platform admin may step in once `timeout_secs` pass
+    pub fn create_escrow(
+        env: Env,
+        payer: Address,
+        payee: Address,
+        token_address: Address,
+        amount: i128,
+        timeout_secs: u64,
+    ) -> Result<u64, BillingError> {
+        payer.require_auth();
+
+        if amount <= 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&payer, &env.current_contract_address(), &amount);
+
+        let escrow_id: u64 = env.storage().instance().get(&ESCROW_SEQ).unwrap_or(0) + 1;
+        env.storage().instance().set(&ESCROW_SEQ, &escrow_id);
+
+        let now = env.ledger().timestamp();
+        let escrow = Escrow {
+            payer,
+            payee,
+            token: token_address,
+            amount,
+            created_at: now,
+            timeout_at: now + timeout_secs,
+            settled: false,
+        };
+        Self::store_escrow(&env, escrow_id, &escrow);
+
+        Ok(escrow_id)
+    }
+
+    // Payer releases the escrowed funds to the payee
+    pub fn release_escrow(env: Env, payer: Address, escrow_id: u64) -> Result<(), BillingError> {
+        payer.require_auth();
+
+        let escrow = Self::get_escrow(env.clone(), escrow_id).ok_or(BillingError::EscrowNotFound)?;
+        if escrow.payer != payer {
+            return Err(BillingError::Unauthorized);
+        }
+
+        let payee = escrow.payee.clone();
+        Self::settle_escrow(&env, escrow_id, escrow, &payee)
+    }
+
+    // Admin escape hatch for an escrow nobody settled before its timeout: sends the
+    // funds to either the payer or the payee and emits ESCROW_FORCE_SETTLED
+    pub fn admin_settle_escrow(
+        env: Env,
+        admin: Address,
+        escrow_id: u64,
+        to: Address,
+    ) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+
+        let escrow = Self::get_escrow(env.clone(), escrow_id).ok_or(BillingError::EscrowNotFound)?;
+        if env.ledger().timestamp() < escrow.timeout_at {
+            return Err(BillingError::EscrowTimeoutNotElapsed);
+        }
+        if to != escrow.payer && to != escrow.payee {
+            return Err(BillingError::InvalidEscrowRecipient);
+        }
+
+        let amount = escrow.amount;
+        Self::settle_escrow(&env, escrow_id, escrow, &to)?;
+
+        env.events().publish(
+            (Symbol::new(&env, "ESCROW_FORCE_SETTLED"), escrow_id),
+            (admin, to, amount),
+        );
+
+        Ok(())
+    }
+
+    pub fn get_escrow(env: Env, escrow_id: u64) -> Option<Escrow> {
+        let escrows: Map<u64, Escrow> = env.storage().persistent().get(&ESCROWS)?;
+        escrows.get(escrow_id)
+    }
+
+    // === STORAGE HEALTH ===
+
+    // Extend the TTL of the core persistent maps to `extend_to` ledgers and remember
+    // the ledger each one now lives until, for get_storage_health
+    pub fn extend_storage_ttl(env: Env, admin: Address, extend_to: u32) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+
+        let live_until = env.ledger().sequence() + extend_to;
+        let mut ttls: Map<Symbol, u32> = env
+            .storage()
+            .instance()
+            .get(&STORAGE_TTLS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        for key in Self::core_storage_keys(&env).iter() {
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().extend_ttl(&key, extend_to, extend_to);
+                ttls.set(key, live_until);
+            }
+        }
+        env.storage().instance().set(&STORAGE_TTLS, &ttls);
+        Ok(())
+    }
+
+    // Ledgers left before each core map may expire. A key never extended through
+    // extend_storage_ttl reports 0, so monitoring flags it until it is.
+    pub fn get_storage_health(env: Env) -> Vec<(Symbol, u32)> {
+        let ttls: Map<Symbol, u32> = env
+            .storage()
+            .instance()
+            .get(&STORAGE_TTLS)
+            .unwrap_or_else(|| Map::new(&env));
+        let current = env.ledger().sequence();
+
+        let mut health = Vec::new(&env);
+        for key in Self::core_storage_keys(&env).iter() {
+            let remaining = ttls.get(key.clone()).unwrap_or(0).saturating_sub(current);
+            health.push_back((key, remaining));
+        }
+        health
+    }
+
+    // === OVERDUE REPUTATION ===
+
+    // Reputation taken from a customer when a bill goes overdue, and given for paying on time
+    pub fn set_reputation_policy(
+        env: Env,
+        admin: Address,
+        overdue_penalty: u32,
+        on_time_bonus: u32,
+    ) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage()
+            .instance()
+            .set(&REPUTATION_POLICY, &(overdue_penalty, on_time_bonus));
+        Ok(())
+    }
+
+    // (overdue penalty, on-time bonus); both zero until configured
+    pub fn get_reputation_policy(env: Env) -> (u32, u32) {
+        env.storage().instance().get(&REPUTATION_POLICY).unwrap_or((0, 0))
+    }
+
+    // End of the grace period for the cycle after the meter's last payment, or None
+    // if the meter has never been paid or has no config
+    pub fn get_payment_due_date(env: Env, meter_id: String) -> Option<u64> {
+        let (last_paid, _) = Self::get_last_payment(env.clone(), meter_id.clone())?;
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id)?;
+        let config = MultiUtilityManager::resolve_meter_config(env, &meter)?;
+        Some(last_paid + (config.billing_cycle_days + config.grace_period_days) as u64 * 86400)
+    }
+
+    // Late fee a meter would owe now on a bill of `balance`, per its config
+    pub fn get_late_fee(env: Env, meter_id: String, balance: i128) -> Result<i128, BillingError> {
+        let (_, config) = Self::load_billable_meter(&env, &meter_id)?;
+        Ok(Self::late_fee_for(&env, &meter_id, &config, balance))
+    }
+
+    // Penalize the customer of an overdue meter in UserManagement. Callable by the
+    // billing admin or a keeper, and only once per missed due date. The billing
+    // contract must hold the Admin role in the linked UserManagement.
+    pub fn apply_overdue_penalty(
+        env: Env,
+        caller: Address,
+        meter_id: String,
+    ) -> Result<(), BillingError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&BILLING_ADMIN)
+            .ok_or(BillingError::NotInitialized)?;
+        Self::require_owner_or_keeper(&env, &caller, &admin)?;
+        let user_mgmt = Self::get_user_management_contract(env.clone())
+            .ok_or(BillingError::UserManagementNotLinked)?;
+
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        let due = Self::get_payment_due_date(env.clone(), meter_id.clone())
+            .ok_or(BillingError::NotOverdue)?;
+        if env.ledger().timestamp() <= due {
+            return Err(BillingError::NotOverdue);
+        }
+
+        let penalized_key = format!("{}_penalized", meter_id);
+        let last_penalized: Option<u64> = env.storage().persistent().get(&penalized_key);
+        if last_penalized == Some(due) {
+            return Err(BillingError::PenaltyAlreadyApplied);
+        }
+        env.storage().persistent().set(&penalized_key, &due);
+
+        let (overdue_penalty, _) = Self::get_reputation_policy(env.clone());
+        if overdue_penalty > 0 {
+            Self::adjust_customer_reputation(&env, &user_mgmt, &meter.customer_address, -(overdue_penalty as i32));
+        }
+
+        env.events()
+            .publish((symbol_short!("OVERDUE"), meter_id), (due, overdue_penalty));
+
+        Ok(())
+    }
+
+    // === PROMOTIONS ===
+
+    // Create or replace a promo code taking `discount_bps` off multi-utility bills
+    pub fn create_promo(
+        env: Env,
+        admin: Address,
+        code: Symbol,
+        discount_bps: u32,
+        max_uses: u32,
+        expires_at: u64,
+    ) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+
+        if discount_bps == 0 || discount_bps > 10000 || max_uses == 0 {
+            return Err(BillingError::InvalidPromo);
+        }
+
+        let mut promos: Map<Symbol, PromoCode> = env
+            .storage()
+            .persistent()
+            .get(&PROMOS)
+            .unwrap_or_else(|| Map::new(&env));
+        promos.set(code, PromoCode { discount_bps, remaining_uses: max_uses, expires_at });
+        env.storage().persistent().set(&PROMOS, &promos);
+        Ok(())
+    }
+
+    pub fn get_promo(env: Env, code: Symbol) -> Option<PromoCode> {
+        let promos: Map<Symbol, PromoCode> = env.storage().persistent().get(&PROMOS)?;
+        promos.get(code)
+    }
+
+    // When strict, paying with an unknown, expired, exhausted or already-used code fails
+    // instead of the code being ignored
+    pub fn set_promo_strict_mode(env: Env, admin: Address, strict: bool) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&PROMO_STRICT, &strict);
+        Ok(())
+    }
+
+    // Conditions (e.g. "senior_citizen") a customer has been verified for, unlocking the
+    // matching config discounts. "early_payment" is earned by paying and needs no grant.
+    pub fn set_discount_eligibility(
+        env: Env,
+        admin: Address,
+        customer: Address,
+        conditions: Vec<String>,
+    ) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut eligibility: Map<Address, Vec<String>> = env
+            .storage()
+            .persistent()
+            .get(&DISCOUNT_ELIGIBILITY)
+            .unwrap_or_else(|| Map::new(&env));
+        eligibility.set(customer, conditions);
+        env.storage().persistent().set(&DISCOUNT_ELIGIBILITY, &eligibility);
+        Ok(())
+    }
+
+    pub fn get_discount_eligibility(env: Env, customer: Address) -> Vec<String> {
+        let eligibility: Map<Address, Vec<String>> = env
+            .storage()
+            .persistent()
+            .get(&DISCOUNT_ELIGIBILITY)
+            .unwrap_or_else(|| Map::new(&env));
+        eligibility.get(customer).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // === KEEPER & AUTOPAY FUNCTIONS ===
+
+    // Register an automation keeper allowed to trigger pre-authorized actions
+    pub fn add_keeper(env: Env, admin: Address, keeper: Address) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut keepers: Map<Address, bool> = env
+            .storage()
+            .instance()
+            .get(&KEEPERS)
+            .unwrap_or_else(|| Map::new(&env));
+        keepers.set(keeper, true);
+        env.storage().instance().set(&KEEPERS, &keepers);
+
+        Ok(())
+    }
+
+    // Remove an automation keeper
+    pub fn remove_keeper(env: Env, admin: Address, keeper: Address) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut keepers: Map<Address, bool> = env
+            .storage()
+            .instance()
+            .get(&KEEPERS)
+            .unwrap_or_else(|| Map::new(&env));
+        keepers.remove(keeper);
+        env.storage().instance().set(&KEEPERS, &keepers);
+
+        Ok(())
+    }
+
+    pub fn is_keeper(env: Env, address: Address) -> bool {
+        let keepers: Map<Address, bool> = env
+            .storage()
+            .instance()
+            .get(&KEEPERS)
+            .unwrap_or_else(|| Map::new(&env));
+        keepers.get(address).unwrap_or(false)
+    }
+
+    // Customer pre-authorizes a fixed recurring payment for their meter.
+    // The customer must also approve this contract as spender on the token.
+    pub fn set_autopay(
+        env: Env,
+        customer: Address,
+        token_address: Address,
+        meter_id: String,
+        amount: i128,
+    ) -> Result<(), BillingError> {
+        customer.require_auth();
+
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+
+        if meter.customer_address != customer {
+            return Err(BillingError::NotMeterCustomer);
+        }
+
+        if amount <= 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+
+        let autopay_key = format!("{}_autopay", meter_id);
+        env.storage()
+            .persistent()
+            .set(&autopay_key, &(customer, token_address, amount));
+
+        Ok(())
+    }
+
+    pub fn get_autopay(env: Env, meter_id: String) -> Option<(Address, Address, i128)> {
+        let autopay_key = format!("{}_autopay", meter_id);
+        env.storage().persistent().get(&autopay_key)
+    }
+
+    // Trigger a pre-authorized autopay. Callable by the customer or a registered keeper;
+    // the amount, token and payer always come from the stored authorization.
+    pub fn execute_autopay(env: Env, caller: Address, meter_id: String) -> Result<i128, BillingError> {
+        Self::when_not_paused(&env)?;
+
+        let (customer, token_address, amount) =
+            Self::get_autopay(env.clone(), meter_id.clone()).ok_or(BillingError::AutopayNotConfigured)?;
+
+        Self::require_owner_or_keeper(&env, &caller, &customer)?;
+
+        // One pull per cycle, however often it is triggered
+        let now = env.ledger().timestamp();
+        if now < Self::get_autopay_next_due(env.clone(), meter_id.clone()) {
+            return Err(BillingError::AutopayNotDue);
+        }
+
+        Self::apply_spending_cap(&env, &meter_id, amount)?;
+
+        // Pull the pre-approved amount from the customer
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer_from(
+            &env.current_contract_address(),
+            &customer,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        Self::add_to_total_paid(&env, &meter_id, amount);
+        Self::record_last_payment(&env, &meter_id, amount);
+        Self::publish_bill_paid(&env, &meter_id, amount, Self::meter_utility_type(&env, &meter_id));
+
+        let due_key = format!("{}_autopay_due", meter_id);
+        env.storage()
+            .persistent()
+            .set(&due_key, &(now + AUTOPAY_CYCLE_SECS));
+
+        Ok(amount)
+    }
+
+    // Earliest timestamp the next autopay may run; 0 until the first execution.
+    // Re-running set_autopay keeps it, so a new authorization can't skip the wait.
+    pub fn get_autopay_next_due(env: Env, meter_id: String) -> u64 {
+        let due_key = format!("{}_autopay_due", meter_id);
+        env.storage().persistent().get(&due_key).unwrap_or(0)
+    }
+
+    // Customer sets a hard per-meter spend cap for each 30-day cycle. The cap is
+    // enforced on every payment path, whoever initiates it; a cap of 0 removes it.
+    pub fn set_spending_cap(
+        env: Env,
+        customer: Address,
+        meter_id: String,
+        monthly_cap: i128,
+    ) -> Result<(), BillingError> {
+        customer.require_auth();
+
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+
+        if meter.customer_address != customer {
+            return Err(BillingError::NotMeterCustomer);
+        }
+
+        if monthly_cap < 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+
+        let cap_key = format!("{}_cap", meter_id);
+        if monthly_cap == 0 {
+            env.storage().persistent().remove(&cap_key);
+            return Ok(());
+        }
+
+        // Changing the cap keeps the current cycle and what was already spent in it
+        let (anchor, spent) = match Self::get_spending_cap(env.clone(), meter_id.clone()) {
+            Some((_, anchor, spent)) => (anchor, spent),
+            None => (env.ledger().timestamp(), 0i128),
+        };
+        env.storage()
+            .persistent()
+            .set(&cap_key, &(monthly_cap, anchor, spent));
+
+        Ok(())
+    }
+
+    // Returns (monthly_cap, cycle_anchor, spent_in_cycle) as last stored
+    pub fn get_spending_cap(env: Env, meter_id: String) -> Option<(i128, u64, i128)> {
+        let cap_key = format!("{}_cap", meter_id);
+        env.storage().persistent().get(&cap_key)
+    }
+
+    // Oracle management functions (delegated to OracleManager)
+    pub fn add_price_feed(env: Env, admin: Address, feed_id: String, price_feed: PriceFeed) {
+        OracleManager::add_price_feed(env, admin, feed_id, price_feed);
+    }
+
+    pub fn update_price_feed(
+        env: Env,
+        feed_id: String,
+        new_price: i128,
+        timestamp: u64,
+    ) -> Result<(), String> {
+        if let Some(oracle) = Self::get_oracle_contract(env.clone()) {
+            oracle.require_auth();
+        }
+        OracleManager::update_price_feed(env, feed_id, new_price, timestamp)
+    }
+
+    pub fn update_price_feeds_batch(env: Env, updates: Vec<(String, i128, u64)>) -> Result<(), String> {
+        if let Some(oracle) = Self::get_oracle_contract(env.clone()) {
+            oracle.require_auth();
+        }
+        OracleManager::update_price_feeds_batch(env, updates)
+    }
+
+    pub fn force_update_price_feed(
+        env: Env,
+        admin: Address,
+        feed_id: String,
+        new_price: i128,
+        timestamp: u64,
+    ) -> Result<(), String> {
+        OracleManager::force_update_price_feed(env, admin, feed_id, new_price, timestamp)
+    }
+
+    pub fn get_price_feed(env: Env, feed_id: String) -> Option<PriceFeed> {
+        OracleManager::get_price_feed(env, feed_id)
+    }
+
+    pub fn remove_price_feed(env: Env, admin: Address, feed_id: String) -> Result<(), String> {
+        OracleManager::remove_price_feed(env, admin, feed_id)
+    }
+
+    pub fn list_price_feeds(env: Env) -> Vec<String> {
+        OracleManager::list_price_feeds(env)
+    }
+
+    pub fn add_utility_rate(env: Env, admin: Address, rate_id: String, utility_rate: UtilityRate) {
+        OracleManager::add_utility_rate(env, admin, rate_id, utility_rate);
+    }
+
+    pub fn update_utility_rate(
+        env: Env,
+        rate_id: String,
+        new_rate: i128,
+        timestamp: u64,
+    ) -> Result<(), String> {
+        OracleManager::update_utility_rate(env, rate_id, new_rate, timestamp)
+    }
+
+    pub fn get_utility_rate(env: Env, rate_id: String) -> Option<UtilityRate> {
+        OracleManager::get_utility_rate(env, rate_id)
+    }
+
+    pub fn add_utility_rate_source(
+        env: Env,
+        admin: Address,
+        utility_type: String,
+        region: String,
+        source_id: String,
+        utility_rate: UtilityRate,
+    ) {
+        OracleManager::add_utility_rate_source(env, admin, utility_type, region, source_id, utility_rate)
+    }
+
+    pub fn get_aggregated_utility_rate(env: Env, utility_type: String, region: String) -> Option<i128> {
+        OracleManager::get_aggregated_utility_rate(env, utility_type, region)
+    }
+
+    pub fn add_price_feed_source(
+        env: Env,
+        admin: Address,
+        feed_id: String,
+        source_index: u32,
+        price_feed: PriceFeed,
+    ) {
+        OracleManager::add_price_feed_source(env, admin, feed_id, source_index, price_feed)
+    }
+
+    pub fn get_aggregated_price(env: Env, feed_id: String) -> Option<i128> {
+        OracleManager::get_aggregated_price(env, feed_id)
+    }
+
+    // Convert currencies at the median of each feed's sources instead of the feed's
+    // own price
+    pub fn set_aggregated_pricing(env: Env, admin: Address, enabled: bool) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&AGGREGATED_PRICING, &enabled);
+        Ok(())
+    }
+
+    pub fn get_aggregated_pricing(env: Env) -> bool {
+        env.storage().instance().get(&AGGREGATED_PRICING).unwrap_or(false)
+    }
+
+    pub fn convert_amount(env: Env, amount: i128, from_currency: String, to_currency: String) -> Result<i128, String> {
+        OracleManager::convert_amount(env, amount, from_currency, to_currency)
+    }
+
+    pub fn get_twap(env: Env, feed_id: String, window_seconds: u64) -> Option<i128> {
+        OracleManager::get_twap(env, feed_id, window_seconds)
+    }
+
+    // Price pay_bill_with_oracle conversions at the TWAP over this window; 0 uses spot
+    pub fn set_twap_window(env: Env, admin: Address, window_seconds: u64) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        env.storage().instance().set(&TWAP_WINDOW, &window_seconds);
+        Ok(())
+    }
+
+    pub fn get_twap_window(env: Env) -> u64 {
+        env.storage().instance().get(&TWAP_WINDOW).unwrap_or(0)
+    }
+
+    pub fn get_oracle_stats(env: Env) -> (oracle::OracleCost, oracle::OracleReliability, u8) {
+        OracleManager::get_oracle_stats(env)
+    }
+
+    pub fn should_update_oracles(env: Env) -> (bool, bool) {
+        (
+            OracleManager::should_update_price_feeds(env.clone()),
+            OracleManager::should_update_utility_rates(env),
+        )
+    }
+
+    // === MULTI-UTILITY FUNCTIONS ===
+
+    // Initialize multi-utility system
+    pub fn initialize_multi_utility(env: Env, admin: Address) {
+        MultiUtilityManager::initialize(env, admin);
+    }
+
+    // Register utility provider
+    pub fn register_utility_provider(
+        env: Env,
+        admin: Address,
+        provider_id: String,
+        name: String,
+        provider_address: Address,
+        utility_type: u8,
+        region: String,
+        license_number: String,
+        contact_info: String,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::register_provider(
+            env,
+            admin,
+            provider_id,
+            name,
+            provider_address,
+            utility_type,
+            region,
+            license_number,
+            contact_info,
+        )
+    }
+
+    // Register a utility provider under a generated, collision-free id
+    pub fn onboard_provider_auto_id(
+        env: Env,
+        admin: Address,
+        name: String,
+        provider_address: Address,
+        utility_type: u8,
+        region: String,
+        license_number: String,
+        contact_info: String,
+    ) -> Result<String, BillingError> {
+        MultiUtilityManager::register_provider_auto_id(
+            env,
+            admin,
+            name,
+            provider_address,
+            utility_type,
+            region,
+            license_number,
+            contact_info,
+        )
+    }
+
+    // Add utility configuration
+    pub fn add_utility_configuration(
+        env: Env,
+        admin: Address,
+        config_id: String,
+        utility_type: u8,
+        provider_id: String,
+        region: String,
+        base_rate: i128,
+        currency: String,
+        decimals: u32,
+        billing_cycle_days: u32,
+        grace_period_days: u32,
+        minimum_payment: i128,
+        maximum_payment: i128,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::add_utility_config(
+            env,
+            admin,
+            config_id,
+            utility_type,
+            provider_id,
+            region,
+            base_rate,
+            currency,
+            decimals,
+            billing_cycle_days,
+            grace_period_days,
+            minimum_payment,
+            maximum_payment,
+        )
+    }
+
+    // Register utility meter
+    pub fn register_utility_meter(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        utility_type: u8,
+        provider_id: String,
+        customer_address: Address,
+        location: String,
+        meter_model: String,
+        firmware_version: String,
+        is_smart_meter: bool,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::register_meter(
+            env,
+            provider_address,
+            meter_id,
+            utility_type,
+            provider_id,
+            customer_address,
+            location,
+            meter_model,
+            firmware_version,
+            is_smart_meter,
+        )
+    }
+
+    // Provider disconnects a meter
+    pub fn disconnect_meter(env: Env, provider_address: Address, meter_id: String) -> Result<(), BillingError> {
+        MultiUtilityManager::disconnect_meter(env, provider_address, meter_id)
+    }
+
+    // Provider switches a meter to or from prepaid billing
+    pub fn set_meter_prepaid(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        is_prepaid: bool,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::set_meter_prepaid(env, provider_address, meter_id, is_prepaid)
+    }
+
+    // Provider transfers a meter to a new customer. The meter's prepaid balance and
+    // overpayment credit are keyed by meter and move with it; settle them with the
+    // outgoing customer (see get_prepaid_balance / get_meter_credit) before transferring.
+    pub fn transfer_meter(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        new_customer: Address,
+    ) -> Result<Address, BillingError> {
+        MultiUtilityManager::transfer_meter(env, provider_address, meter_id, new_customer)
+    }
+
+    // Provider reconnects a meter after its reconnection cooldown
+    pub fn reconnect_meter(env: Env, provider_address: Address, meter_id: String) -> Result<(), BillingError> {
+        MultiUtilityManager::reconnect_meter(env, provider_address, meter_id)
+    }
+
+    pub fn get_reconnection_eligible_at(env: Env, meter_id: String) -> Option<u64> {
+        MultiUtilityManager::get_reconnection_eligible_at(env, meter_id)
+    }
+
+    // Provider appends a maintenance note to a meter's log
+    pub fn add_meter_note(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        note: String,
+        category: Symbol,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::add_meter_note(env, provider_address, meter_id, note, category)
+    }
+
+    pub fn get_meter_notes(env: Env, meter_id: String, start: u32, limit: u32) -> Vec<MeterNote> {
+        MultiUtilityManager::get_meter_notes(env, meter_id, start, limit)
+    }
+
+    // Lifetime consumption billed to a meter, for usage reports
+    pub fn get_total_consumption(env: Env, meter_id: String) -> i128 {
+        MultiUtilityManager::get_total_consumption(env, meter_id)
+    }
+
+    // Submit a single smart meter reading, returning the consumption since the last one
+    pub fn submit_meter_reading(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        new_reading: i128,
+        reading_timestamp: u64,
+    ) -> Result<i128, BillingError> {
+        MultiUtilityManager::submit_meter_reading(env, provider_address, meter_id, new_reading, reading_timestamp)
+    }
+
+    // Submit a batch of smart meter readings
+    pub fn submit_readings_batch(
+        env: Env,
+        provider_address: Address,
+        readings: Vec<(String, i128, u64)>,
+    ) -> Result<Vec<ReadingResult>, BillingError> {
+        MultiUtilityManager::submit_readings_batch(env, provider_address, readings)
+    }
+
+    // Add utility fee
+    pub fn add_utility_fee_structure(
+        env: Env,
+        admin: Address,
+        fee_id: String,
+        utility_type: u8,
+        provider_id: String,
+        fee_type: u8,
+        fee_amount: i128,
+        fee_percentage: Option<i128>,
+        is_percentage: bool,
+        description: String,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::add_utility_fee(
+            env,
+            admin,
+            fee_id,
+            utility_type,
+            provider_id,
+            fee_type,
+            fee_amount,
+            fee_percentage,
+            is_percentage,
+            description,
+        )
+    }
+
+    // Enhanced multi-utility payment function
+    pub fn pay_multi_utility_bill(
+        env: Env,
+        from: Address,
+        token_address: Address,
+        meter_id: String,
+        consumption: i128,
+        currency: Option<String>,
+        apply_fees: bool,
+        promo: Option<Symbol>,
+        amount_tendered: Option<i128>,
+    ) -> Result<PaymentReceipt, BillingError> {
+        Self::charge_multi_utility_bill(
+            env, from, token_address, meter_id, consumption, currency, apply_fees, promo,
+            amount_tendered, None,
+        )
+    }
+
+    // Payment flow behind pay_multi_utility_bill and end_charging_session. A pricing
+    // window prices time-of-use over that span instead of at the current ledger time.
+    fn charge_multi_utility_bill(
+        env: Env,
+        from: Address,
+        token_address: Address,
+        meter_id: String,
+        consumption: i128,
+        currency: Option<String>,
+        apply_fees: bool,
+        promo: Option<Symbol>,
+        amount_tendered: Option<i128>,
+        pricing_window: Option<(u64, u64)>,
+    ) -> Result<PaymentReceipt, BillingError> {
+        Self::when_not_paused(&env)?;
+
+        // 1. Verify authorization
+        from.require_auth();
+
+        // 2-3. Price the bill and check it against the meter's limits
+        let (meter, mut quote, applied_promo) = Self::prepare_multi_utility_bill(
+            &env, &from, &meter_id, consumption, currency, apply_fees, promo, pricing_window,
+        )?;
+
+        // 4-8. Apply stored value, record the bill and log the payment
+        let transfer = Self::charge_quoted_bill(
+            &env, &from, &meter_id, consumption, meter, &mut quote, amount_tendered,
+        )?;
+        if let Some(code) = applied_promo {
+            Self::redeem_promo(&env, &from, &code, quote.promo_discount);
+        }
+        if transfer > 0 {
+            let token_client = token::Client::new(&env, &token_address);
+            token_client.transfer(&from, &env.current_contract_address(), &transfer);
+        }
+
+        // 9. Receipt in both the customer's and the provider's currency
+        let receipt = PaymentReceipt {
+            meter_id: meter_id.clone(),
+            base_amount: quote.base_amount,
+            overage_amount: quote.overage_amount,
+            discount_amount: quote.discount_amount,
+            original_amount: quote.final_amount,
+            original_currency: quote.paid_currency,
+            settled_amount: quote.settled_amount,
+            settled_currency: quote.settled_currency,
+            exchange_rate: quote.exchange_rate,
+            rate_decimals: quote.rate_decimals,
+        };
+        env.events()
+            .publish((symbol_short!("RECEIPT"), meter_id), receipt.clone());
+
+        Ok(receipt)
+    }
+
+    // Provider credits a customer for energy their solar meter exported to the grid, at
+    // the solar config's base rate. The credit offsets the customer's electricity bills.
+    pub fn record_solar_export(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        kwh_exported: i128,
+    ) -> Result<i128, BillingError> {
+        provider_address.require_auth();
+
+        let (meter, config) = Self::load_billable_meter(&env, &meter_id)?;
+        let provider = MultiUtilityManager::get_provider(env.clone(), meter.provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        if provider.address != provider_address {
+            return Err(BillingError::UnauthorizedProvider);
+        }
+        if meter.utility_type != UtilityType::Solar {
+            return Err(BillingError::UtilityTypeMismatch);
+        }
+        if kwh_exported <= 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+
+        let credit = MultiUtilityManager::charge_for_consumption(&config, kwh_exported, config.base_rate);
+
+        let mut credits: Map<Address, i128> = env
+            .storage()
+            .persistent()
+            .get(&SOLAR_CREDITS)
+            .unwrap_or_else(|| Map::new(&env));
+        let balance = credits.get(meter.customer_address.clone()).unwrap_or(0) + credit;
+        credits.set(meter.customer_address.clone(), balance);
+        env.storage().persistent().set(&SOLAR_CREDITS, &credits);
+
+        env.events()
+            .publish((symbol_short!("SOLAR_EXP"), meter_id), (kwh_exported, credit));
+
+        Ok(credit)
+    }
+
+    // Unused solar export credit held for a customer
+    pub fn get_solar_credit(env: Env, customer: Address) -> i128 {
+        let credits: Map<Address, i128> = env
+            .storage()
+            .persistent()
+            .get(&SOLAR_CREDITS)
+            .unwrap_or_else(|| Map::new(&env));
+        credits.get(customer).unwrap_or(0)
+    }
+
+    // Open a charging session on an EV charging meter, returning its id. A meter runs
+    // one session at a time.
+    pub fn start_charging_session(env: Env, meter_id: String) -> Result<u64, BillingError> {
+        let (meter, _) = Self::load_billable_meter(&env, &meter_id)?;
+        meter.customer_address.require_auth();
+
+        if meter.utility_type != UtilityType::EVCharging {
+            return Err(BillingError::UtilityTypeMismatch);
+        }
+
+        let session_key = format!("{}_ev_session", meter_id);
+        if env.storage().persistent().has(&session_key) {
+            return Err(BillingError::ChargingSessionActive);
+        }
+
+        let seq_key = format!("{}_ev_seq", meter_id);
+        let session_id: u64 = env.storage().persistent().get(&seq_key).unwrap_or(0) + 1;
+        let started_at = env.ledger().timestamp();
+        env.storage().persistent().set(&seq_key, &session_id);
+        env.storage().persistent().set(&session_key, &(session_id, started_at));
+
+        env.events()
+            .publish((symbol_short!("EV_START"), meter_id), (session_id, started_at));
+
+        Ok(session_id)
+    }
+
+    // Active charging session on a meter as (session_id, started_at)
+    pub fn get_charging_session(env: Env, meter_id: String) -> Option<(u64, u64)> {
+        env.storage().persistent().get(&format!("{}_ev_session", meter_id))
+    }
+
+    // Close a charging session and pay for the energy delivered in the charger's currency,
+    // pricing time-of-use over the hours the session spanned
+    pub fn end_charging_session(
+        env: Env,
+        from: Address,
+        token_address: Address,
+        meter_id: String,
+        session_id: u64,
+        kwh_delivered: i128,
+    ) -> Result<PaymentReceipt, BillingError> {
+        let (_, config) = Self::load_billable_meter(&env, &meter_id)?;
+        let (active_id, started_at) = Self::get_charging_session(env.clone(), meter_id.clone())
+            .ok_or(BillingError::ChargingSessionNotFound)?;
+        if active_id != session_id {
+            return Err(BillingError::ChargingSessionNotFound);
+        }
+
+        let ended_at = env.ledger().timestamp();
+        let receipt = Self::charge_multi_utility_bill(
+            env.clone(),
+            from,
+            token_address,
+            meter_id.clone(),
+            kwh_delivered,
+            Some(config.currency),
+            false,
+            None,
+            None,
+            Some((started_at, ended_at)),
+        )?;
+        env.storage().persistent().remove(&format!("{}_ev_session", meter_id));
+
+        env.events().publish(
+            (symbol_short!("EV_END"), meter_id),
+            (session_id, ended_at - started_at, kwh_delivered),
+        );
+
+        Ok(receipt)
+    }
+
+    // Deposit tokens into a meter's prepaid balance
+    pub fn top_up_meter(
+        env: Env,
+        from: Address,
+        token_address: Address,
+        meter_id: String,
+        amount: i128,
+    ) -> Result<(), BillingError> {
+        Self::when_not_paused(&env)?;
+        from.require_auth();
+
+        MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        if amount <= 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&from, &env.current_contract_address(), &amount);
+
+        let prepaid_key = format!("{}_prepaid", meter_id);
+        let balance = Self::read_prepaid_balance(&env, &meter_id);
+        env.storage().persistent().set(&prepaid_key, &(balance + amount));
+
+        env.events()
+            .publish((symbol_short!("TOP_UP"), meter_id), (from, amount));
+
+        Ok(())
+    }
+
+    pub fn get_prepaid_balance(env: Env, meter_id: String) -> i128 {
+        Self::read_prepaid_balance(&env, &meter_id)
+    }
+
+    // Overpayment held for a meter and drawn down by its next multi-utility bills,
+    // in the currency those bills were paid in
+    pub fn get_meter_credit(env: Env, meter_id: String) -> i128 {
+        let credit_key = format!("{}_credit", meter_id);
+        env.storage().persistent().get(&credit_key).unwrap_or(0)
+    }
+
+    // Pay several multi-utility bills, given as (meter_id, consumption, currency), with
+    // one auth and one transfer of the combined total. AllOrNothing fails the whole
+    // batch on the first bad meter; BestEffort skips failing meters and reports them.
+    pub fn pay_bills_batch(
+        env: Env,
+        from: Address,
+        token_address: Address,
+        payments: Vec<(String, i128, String)>,
+        policy: BatchPolicy,
+    ) -> Result<Vec<BatchPaymentResult>, BillingError> {
+        Self::when_not_paused(&env)?;
+        from.require_auth();
+
+        if payments.len() > MAX_PAYMENT_BATCH {
+            return Err(BillingError::BatchTooLarge);
+        }
+
+        let mut results = Vec::new(&env);
+        let mut total: i128 = 0;
+        for (meter_id, consumption, currency) in payments.iter() {
+            let outcome = Self::prepare_multi_utility_bill(
+                &env, &from, &meter_id, consumption, Some(currency), false, None, None,
+            )
+            .and_then(|(meter, mut quote, _)| {
+                Self::charge_quoted_bill(&env, &from, &meter_id, consumption, meter, &mut quote, None)
+            });
+
+            let result = match outcome {
+                Ok(transfer) => {
+                    total += transfer;
+                    BatchPaymentResult {
+                        meter_id,
+                        paid: true,
+                        amount: transfer,
+                        error_code: 0,
+                    }
+                }
+                Err(error) => {
+                    if policy == BatchPolicy::AllOrNothing {
+                        return Err(error);
+                    }
+                    BatchPaymentResult {
+                        meter_id,
+                        paid: false,
+                        amount: 0,
+                        error_code: error as u32,
+                    }
+                }
+            };
+            results.push_back(result);
+        }
+
+        if total > 0 {
+            let token_client = token::Client::new(&env, &token_address);
+            token_client.transfer(&from, &env.current_contract_address(), &total);
+        }
+
+        Ok(results)
+    }
+
+    // Quote a multi-utility bill exactly as pay_multi_utility_bill would price it, with
+    // the same limit checks, but without requiring auth, moving tokens or recording
+    // anything. Meant for read-only simulation from frontends.
+    pub fn simulate_payment(
+        env: Env,
+        from: Address,
+        meter_id: String,
+        consumption: i128,
+        currency: Option<String>,
+        apply_fees: bool,
+        promo: Option<Symbol>,
+    ) -> Result<BillQuote, BillingError> {
+        let (_, quote, _) = Self::prepare_multi_utility_bill(
+            &env, &from, &meter_id, consumption, currency, apply_fees, promo, None,
+        )?;
+        Ok(quote)
+    }
+
+    // Preview a meter's bill with fees applied as (base, tax, fee, discount, final), priced
+    // by the same path as pay_multi_utility_bill without auth, transfers or storage writes
+    pub fn estimate_multi_utility_bill(
+        env: Env,
+        meter_id: String,
+        consumption: i128,
+        currency: Option<String>,
+    ) -> Result<(i128, i128, i128, i128, i128), BillingError> {
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        let (_, quote, _) = Self::prepare_multi_utility_bill(
+            &env, &meter.customer_address, &meter_id, consumption, currency, true, None, None,
+        )?;
+        Ok((
+            quote.base_amount + quote.overage_amount,
+            quote.tax_amount,
+            quote.fee_amount,
+            quote.discount_amount,
+            quote.final_amount,
+        ))
+    }
+
+    // Set a default tax for a region and utility type
+    pub fn set_default_tax_rate(
+        env: Env,
+        admin: Address,
+        region: String,
+        utility_type: u8,
+        tax: TaxRate,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::set_default_tax(env, admin, region, utility_type, tax)
+    }
+
+    // Blended per-unit rate for a consumption after tiers, time-of-use and volume discounts, before taxes and fees
+    pub fn effective_rate(env: Env, meter_id: String, consumption: i128) -> Result<i128, BillingError> {
+        let (_, config) = Self::load_billable_meter(&env, &meter_id)?;
+
+        if consumption == 0 {
+            return Ok(config.base_rate);
+        }
+        if consumption < 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+
+        let base_amount = Self::calculate_base_amount(&env, &config, consumption, None);
+        Ok((base_amount * 10_i128.pow(config.consumption_decimals)) / consumption)
+    }
+
+    // Activate or deactivate a fee
+    pub fn update_utility_fee_status(
+        env: Env,
+        admin: Address,
+        fee_id: String,
+        is_active: bool,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::update_fee_status(env, admin, fee_id, is_active)
+    }
+
+    // List a provider's fees
+    pub fn list_provider_fees(env: Env, provider_id: String, active_only: bool) -> Vec<UtilityFee> {
+        MultiUtilityManager::list_provider_fees(env, provider_id, active_only)
+    }
+
+    // Get utility provider
+    pub fn get_utility_provider(env: Env, provider_id: String) -> Option<UtilityProvider> {
+        MultiUtilityManager::get_provider(env, provider_id)
+    }
+
+    // Get utility configuration
+    pub fn get_utility_configuration(env: Env, config_id: String) -> Option<UtilityConfig> {
+        MultiUtilityManager::get_utility_config(env, config_id)
+    }
+
+    // Get utility meter
+    pub fn get_utility_meter_info(env: Env, meter_id: String) -> Option<UtilityMeter> {
+        MultiUtilityManager::get_meter(env, meter_id)
+    }
+
+    // All meters registered to a customer
+    pub fn list_meters_by_customer(env: Env, customer: Address) -> Vec<UtilityMeter> {
+        MultiUtilityManager::list_meters_by_customer(env, customer)
+    }
+
+    // Active configuration the billing path would use for a meter, if any
+    pub fn get_meter_config(env: Env, meter_id: String) -> Option<UtilityConfig> {
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id)?;
+        let config = MultiUtilityManager::resolve_meter_config(env, &meter)?;
+
+        if config.is_active {
+            Some(config)
+        } else {
+            None
+        }
+    }
+
+    // Whether a price feed prices a config's currency at the config's precision
+    pub fn check_decimal_consistency(env: Env, config_id: String, feed_id: String) -> bool {
+        MultiUtilityManager::check_decimal_consistency(env, config_id, feed_id)
+    }
+
+    // Get utility fee
+    pub fn get_utility_fee_info(env: Env, fee_id: String) -> Option<UtilityFee> {
+        MultiUtilityManager::get_utility_fee(env, fee_id)
+    }
+
+    // List providers by type and region
+    pub fn list_providers(
+        env: Env,
+        utility_type: u8,
+        region: String,
+    ) -> Result<Vec<UtilityProvider>, BillingError> {
+        MultiUtilityManager::list_providers_by_type_and_region(env, utility_type, region)
+    }
+
+    // Update provider status
+    pub fn update_provider_status(
+        env: Env,
+        admin: Address,
+        provider_id: String,
+        is_active: bool,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::update_provider_status(env, admin, provider_id, is_active)
+    }
+
+    // Active flag for many providers at once; None marks an unknown provider id
+    pub fn get_providers_status(
+        env: Env,
+        provider_ids: Vec<String>,
+    ) -> Result<Vec<(String, Option<bool>)>, BillingError> {
+        MultiUtilityManager::get_providers_status(env, provider_ids)
+    }
+
+    // Customer sets their default provider for a utility type
+    pub fn set_preferred_provider(
+        env: Env,
+        customer: Address,
+        utility_type: u8,
+        provider_id: String,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::set_preferred_provider(env, customer, utility_type, provider_id)
+    }
+
+    pub fn get_preferred_provider(env: Env, customer: Address, utility_type: u8) -> Option<String> {
+        MultiUtilityManager::get_preferred_provider(env, customer, utility_type)
+    }
+
+    // Customer rates a provider from 1 to 5 stars
+    pub fn submit_provider_rating(
+        env: Env,
+        customer: Address,
+        provider_id: String,
+        score: u8,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::submit_provider_rating(env, customer, provider_id, score)
+    }
+
+    // Count of 1..5 star ratings for a provider, index 0 being 1 star
+    pub fn get_provider_rating_distribution(env: Env, provider_id: String) -> [u32; 5] {
+        MultiUtilityManager::get_rating_distribution(env, provider_id)
+    }
+
+    // Deactivate a provider along with its configs and meters (resumable)
+    pub fn deactivate_provider_cascade(
+        env: Env,
+        admin: Address,
+        provider_id: String,
+    ) -> Result<(u32, u32, bool), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        MultiUtilityManager::deactivate_provider_cascade(env, admin, provider_id)
+    }
+
+    // Remove a provider that no active meter or config references
+    pub fn deregister_provider(env: Env, admin: Address, provider_id: String) -> Result<(), BillingError> {
+        Self::require_admin(&env, &admin)?;
+        MultiUtilityManager::deregister_provider(env, admin, provider_id)
+    }
+
+    // Append a tier to a config without creating a new version
+    pub fn add_tier_rate(env: Env, admin: Address, config_id: String, tier: TierRate) -> Result<(), BillingError> {
+        MultiUtilityManager::add_tier_rate(env, admin, config_id, tier)
+    }
+
+    // Append a time-of-use rate to a config without creating a new version
+    pub fn add_tou_rate(env: Env, admin: Address, config_id: String, rate: TimeOfUseRate) -> Result<(), BillingError> {
+        MultiUtilityManager::add_tou_rate(env, admin, config_id, rate)
+    }
+
+    // Append a tax to a config without creating a new version
+    pub fn add_tax_rate(env: Env, admin: Address, config_id: String, tax: TaxRate) -> Result<(), BillingError> {
+        MultiUtilityManager::add_tax_rate(env, admin, config_id, tax)
+    }
+
+    // Append a discount to a config without creating a new version
+    pub fn add_discount_rate(env: Env, admin: Address, config_id: String, discount: DiscountRate) -> Result<(), BillingError> {
+        MultiUtilityManager::add_discount_rate(env, admin, config_id, discount)
+    }
+
+    // Upgrade utility configuration
+    pub fn upgrade_utility_configuration(
+        env: Env,
+        admin: Address,
+        config_id: String,
+        new_config: UtilityConfig,
+        allow_currency_change: bool,
+        migration_note: Option<String>,
+    ) -> Result<(), BillingError> {
+        MultiUtilityManager::upgrade_utility_config(
+            env,
+            admin,
+            config_id,
+            new_config,
+            allow_currency_change,
+            migration_note,
+        )
+    }
+
+    // Get a recorded configuration version
+    pub fn get_config_version(env: Env, config_id: String, version: u32) -> Option<UtilityVersion> {
+        MultiUtilityManager::get_config_version(env, config_id, version)
+    }
+
+    // List recorded configuration versions
+    pub fn list_config_versions(env: Env, config_id: String) -> Vec<u32> {
+        MultiUtilityManager::list_config_versions(env, config_id)
+    }
+
+    // Validate utility type
+    pub fn validate_utility_type(env: Env, utility_type: u8) -> Result<(), BillingError> {
+        MultiUtilityManager::validate_utility_type(env, utility_type)
+    }
+
+    // Get all utility types
+    pub fn get_supported_utility_types(env: Env) -> soroban_sdk::Map<u8, String> {
+        MultiUtilityManager::get_utility_types(env)
+    }
+
+    // Get code, name, unit and metered/flat billing for each supported utility type
+    pub fn get_utility_type_details(env: Env) -> Vec<UtilityTypeDetails> {
+        MultiUtilityManager::get_utility_type_details(env)
+    }
+
+    // === UPGRADE MANAGEMENT FUNCTIONS ===
+
+    // Initialize upgrade systems
+    pub fn initialize_upgrade_system(env: Env, admin: Address) -> Result<(), Symbol> {
+        UpgradeProxy::initialize(env.clone(), admin.clone())?;
+        VersionManager::initialize(env.clone(), admin.clone());
+        DataMigration::initialize(env, admin);
+        Ok(())
+    }
+
+    // Dry-run of upgrade_contract: returns the first reason the upgrade to
+    // `target_version` would be blocked, or Ok if it can run end-to-end
+    pub fn can_upgrade_to(env: Env, target_version: u32) -> Result<(), Symbol> {
+        let target = VersionManager::get_version_info(env.clone(), target_version)
+            .ok_or(Symbol::short("TO_VERSION_NOT_FOUND"))?;
+
+        if target.deprecated {
+            return Err(Symbol::short("VER_DEPR"));
+        }
+
+        let current_version = UpgradeProxy::get_version(env.clone());
+        if target_version <= current_version {
+            return Err(Symbol::short("VER_LOWER"));
+        }
+
+        if VersionManager::get_version_info(env.clone(), current_version).is_none() {
+            return Err(Symbol::short("FROM_VERSION_NOT_FOUND"));
+        }
+
+        if !VersionManager::is_upgrade_safe(env.clone(), current_version, target_version)? {
+            return Err(Symbol::short("UNSAFE_UPGRADE"));
+        }
+
+        // Any chain of registered scripts will do, not just a direct one
+        if target.migration_required
+            && DataMigration::get_migration_path(env, current_version, target_version).is_empty()
+        {
+            return Err(Symbol::short("MIGRATION_NOT_FOUND"));
+        }
+
+        Ok(())
+    }
+
+    // Upgrade contract to new version in one step. Only available while the proxy has
+    // no timelock or approval threshold; otherwise use propose/execute_contract_upgrade.
+    pub fn upgrade_contract(
+        env: Env,
+        admin: Address,
+        new_implementation: Address,
+        new_version: u32,
+    ) -> Result<(), Symbol> {
+        // Check the upgrade path is executable end-to-end
+        let current_version = UpgradeProxy::get_version(env.clone());
+        Self::can_upgrade_to(env.clone(), new_version)?;
+
+        // Backup data before upgrade
+        DataMigration::backup_data(env.clone(), admin.clone())?;
+
+        // Execute upgrade
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), new_implementation, new_version)?;
+
+        // Execute data migration if needed
+        let version_info = VersionManager::get_version_info(env.clone(), new_version);
+        if let Some(info) = version_info {
+            if info.migration_required {
+                Self::run_migration_path(&env, &admin, current_version, new_version)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Propose an upgrade to be executed once the proxy's timelock and approvals allow.
+    // The preflight runs now for early feedback and again at execution.
+    pub fn propose_contract_upgrade(
+        env: Env,
+        admin: Address,
+        new_implementation: Address,
+        new_version: u32,
+    ) -> Result<(), Symbol> {
+        Self::can_upgrade_to(env.clone(), new_version)?;
+        UpgradeProxy::propose_upgrade(env, admin, new_implementation, new_version)
+    }
+
+    // Approver signs off on the pending contract upgrade
+    pub fn approve_contract_upgrade(env: Env, approver: Address) -> Result<(), Symbol> {
+        UpgradeProxy::approve_upgrade(env, approver)
+    }
+
+    // Drop the pending contract upgrade
+    pub fn cancel_contract_upgrade(env: Env, admin: Address) -> Result<(), Symbol> {
+        UpgradeProxy::cancel_upgrade(env, admin)
+    }
+
+    // Execute the pending upgrade with the same preflight, backup and migration steps
+    // as upgrade_contract
+    pub fn execute_contract_upgrade(env: Env, admin: Address) -> Result<(), Symbol> {
+        let (_, new_version, _) = UpgradeProxy::get_pending_upgrade(env.clone())
+            .ok_or(Symbol::short("NO_PENDNG"))?;
+
+        // Things may have changed while the proposal waited
+        let current_version = UpgradeProxy::get_version(env.clone());
+        Self::can_upgrade_to(env.clone(), new_version)?;
+
+        // Backup data before upgrade
+        DataMigration::backup_data(env.clone(), admin.clone())?;
+
+        UpgradeProxy::execute_upgrade(env.clone(), admin.clone())?;
+
+        // Execute data migration if needed
+        let version_info = VersionManager::get_version_info(env.clone(), new_version);
+        if let Some(info) = version_info {
+            if info.migration_required {
+                Self::run_migration_path(&env, &admin, current_version, new_version)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Run each hop of the migration chain from `from_version` to `to_version` in order
+    fn run_migration_path(env: &Env, admin: &Address, from_version: u32, to_version: u32) -> Result<(), Symbol> {
+        let path = DataMigration::get_migration_path(env.clone(), from_version, to_version);
+        if path.is_empty() {
+            return Err(Symbol::short("MIGRATION_NOT_FOUND"));
+        }
+        for script in path.iter() {
+            DataMigration::execute_migration(env.clone(), admin.clone(), script.from_version, script.to_version)?;
+        }
+        Ok(())
+    }
+
+    // Register new contract version
+    pub fn register_contract_version(
+        env: Env,
+        admin: Address,
+        version: u32,
+        implementation_address: Address,
+        migration_required: bool,
+        backward_compatible: bool,
+    ) -> Result<(), Symbol> {
+        VersionManager::register_version(
+            env,
+            admin,
+            version,
+            implementation_address,
+            migration_required,
+            backward_compatible,
+        )
+    }
+
+    // Deprecate a contract version so it can no longer be upgraded to
+    pub fn deprecate_contract_version(env: Env, admin: Address, version: u32) -> Result<(), Symbol> {
+        VersionManager::deprecate_version(env, admin, version)
+    }
+
+    // Chain of migration scripts connecting two versions, empty if there is none
+    pub fn get_migration_path(env: Env, from_version: u32, to_version: u32) -> Vec<MigrationScript> {
+        DataMigration::get_migration_path(env, from_version, to_version)
+    }
+
+    // Get current contract version
+    pub fn get_contract_version(env: Env) -> u32 {
+        UpgradeProxy::get_version(env)
+    }
+
+    // Get contract upgrade info
+    pub fn get_upgrade_info(env: Env) -> (u32, Address, bool) {
+        let version = UpgradeProxy::get_version(env.clone());
+        let implementation = UpgradeProxy::get_implementation(env.clone());
+        let admin = UpgradeProxy::get_admin(env);
+        (version, implementation, admin == env.current_contract_address())
+    }
+
+    // List all contract versions
+    pub fn list_contract_versions(env: Env) -> soroban_sdk::Map<u32, ContractVersion> {
+        VersionManager::list_versions(env)
+    }
+
+    // Check if upgrade is available
+    pub fn is_upgrade_available(env: Env) -> bool {
+        let current_version = UpgradeProxy::get_version(env.clone());
+        if let Some(latest_version) = VersionManager::get_latest_version(env) {
+            return latest_version > current_version;
+        }
+        false
+    }
+
+    // Get migration status
+    pub fn get_migration_status(env: Env) -> (bool, Option<u32>) {
+        let current_version = UpgradeProxy::get_version(env.clone());
+        let version_info = VersionManager::get_version_info(env, current_version);
+        
+        match version_info {
+            Some(info) => (info.migration_required, Some(info.version)),
+            None => (false, None),
+        }
+    }
+
+    // === INTERNAL HELPERS ===
+
+    // Load a meter and the active configuration that prices it
+    fn load_billable_meter(env: &Env, meter_id: &String) -> Result<(UtilityMeter, UtilityConfig), BillingError> {
+        // Get meter information
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+
+        if !meter.is_active {
+            return Err(BillingError::MeterNotActive);
+        }
+
+        let provider = MultiUtilityManager::get_provider(env.clone(), meter.provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+
+        if !provider.is_active {
+            return Err(BillingError::ProviderNotActive);
+        }
+
+        // Get utility configuration
+        let config = MultiUtilityManager::resolve_meter_config(env.clone(), &meter)
+            .ok_or(BillingError::ConfigNotFound)?;
 
         if !config.is_active {
-            return Err("Utility configuration is not active".to_string());
+            return Err(BillingError::ConfigNotActive);
         }
 
-        // 4. Calculate base amount
-        let mut base_amount = consumption * config.base_rate;
+        Ok((meter, config))
+    }
 
-        // 5. Apply tier rates if applicable
-        for tier_rate in config.tier_rates.iter() {
-            if consumption >= tier_rate.min_units && consumption <= tier_rate.max_units {
-                base_amount = consumption * tier_rate.rate_per_unit;
-                break;
+    // Price a bill from the meter's utility configuration
+    // Read-only pricing shared by payment and simulation: the meter, its quote with any
+    // promo discount applied, and the promo to redeem if the payment goes through
+    fn prepare_multi_utility_bill(
+        env: &Env,
+        from: &Address,
+        meter_id: &String,
+        consumption: i128,
+        currency: Option<String>,
+        apply_fees: bool,
+        promo: Option<Symbol>,
+        pricing_window: Option<(u64, u64)>,
+    ) -> Result<(UtilityMeter, BillQuote, Option<Symbol>), BillingError> {
+        let currency = Self::resolve_currency(env, currency)?;
+
+        // A meter without a config can still be billed from the oracle rate when
+        // oracle fallback is on
+        let (meter, mut quote) = match Self::load_billable_meter(env, meter_id) {
+            Ok((meter, config)) => {
+                let quote = Self::quote_with_config(
+                    env, &meter, &config, consumption, &currency, apply_fees, pricing_window,
+                )?;
+                (meter, quote)
+            }
+            Err(BillingError::ConfigNotFound) => {
+                Self::quote_with_oracle_rate(env, meter_id, consumption, &currency)?
+            }
+            Err(error) => return Err(error),
+        };
+
+        let mut applied_promo = None;
+        if let Some(code) = promo {
+            if let Some(usable) = Self::usable_promo(env, from, &code)? {
+                quote.promo_discount = quote.final_amount * usable.discount_bps as i128 / 10000;
+                quote.final_amount -= quote.promo_discount;
+                quote.settled_amount -= quote.settled_amount * usable.discount_bps as i128 / 10000;
+                applied_promo = Some(code);
             }
         }
 
-        // 6. Apply time-of-use rates if applicable
-        let current_hour = (env.ledger().timestamp() / 3600) % 24;
-        let current_day_of_week = ((env.ledger().timestamp() / 86400) % 7) as u8;
+        Self::check_spending_cap(env, meter_id, quote.final_amount)?;
+        Ok((meter, quote, applied_promo))
+    }
 
-        for tou_rate in config.time_of_use_rates.iter() {
-            if current_hour >= tou_rate.start_hour
-                && current_hour <= tou_rate.end_hour
-                && tou_rate.days_of_week.contains(current_day_of_week)
-            {
-                base_amount = (base_amount * tou_rate.rate_multiplier) / 100;
-                break;
+    // Charge a priced multi-utility bill, shared by single and batch payments: offset it
+    // with solar export and meter credit, take the rest from the balance of a prepaid
+    // meter, then record the bill and log the payment. Every check runs before anything
+    // is written, so a rejected bill leaves no trace even when the batch carries on.
+    // Returns the amount the caller still has to transfer from `from`.
+    fn charge_quoted_bill(
+        env: &Env,
+        from: &Address,
+        meter_id: &String,
+        consumption: i128,
+        meter: UtilityMeter,
+        quote: &mut BillQuote,
+        amount_tendered: Option<i128>,
+    ) -> Result<i128, BillingError> {
+        // Anyone may pay a bill, but only the customer may spend the meter's stored value
+        if *from != meter.customer_address && Self::holds_stored_value(env, &meter) {
+            return Err(BillingError::NotMeterCustomer);
+        }
+
+        let solar_credit_left = Self::apply_solar_export_credit(env, &meter, quote);
+        let final_amount = quote.final_amount;
+        let cap_state = Self::check_spending_cap(env, meter_id, final_amount)?;
+        let paid_on_time = Self::get_payment_due_date(env.clone(), meter_id.clone())
+            .map(|due| env.ledger().timestamp() <= due)
+            .unwrap_or(false);
+        let customer = meter.customer_address.clone();
+        let is_prepaid = meter.is_prepaid;
+
+        // Draw on the meter's credit first. Prepaid meters pay the rest from their balance;
+        // otherwise anything tendered beyond what is still due becomes credit for later bills.
+        let credit = Self::get_meter_credit(env.clone(), meter_id.clone());
+        let credit_applied = credit.min(final_amount);
+        let amount_due = final_amount - credit_applied;
+        let mut tendered = amount_due;
+        let mut prepaid_left = None;
+        if is_prepaid {
+            let balance = Self::read_prepaid_balance(env, meter_id);
+            if balance < amount_due {
+                return Err(BillingError::InsufficientPrepaidBalance);
+            }
+            prepaid_left = Some(balance - amount_due);
+        } else {
+            tendered = amount_tendered.unwrap_or(amount_due);
+            if tendered < amount_due {
+                return Err(BillingError::InsufficientTender);
+            }
+        }
+
+        if let Some(remaining) = solar_credit_left {
+            let mut credits: Map<Address, i128> = env.storage().persistent().get(&SOLAR_CREDITS).unwrap();
+            credits.set(customer.clone(), remaining);
+            env.storage().persistent().set(&SOLAR_CREDITS, &credits);
+        }
+        if let Some(updated) = cap_state {
+            let cap_key = format!("{}_cap", meter_id);
+            env.storage().persistent().set(&cap_key, &updated);
+        }
+        if let Some(balance) = prepaid_left {
+            let prepaid_key = format!("{}_prepaid", meter_id);
+            env.storage().persistent().set(&prepaid_key, &balance);
+        }
+        let credit_key = format!("{}_credit", meter_id);
+        env.storage()
+            .persistent()
+            .set(&credit_key, &(credit - credit_applied + tendered - amount_due));
+
+        // Record the bill and credit the provider
+        Self::settle_multi_utility_bill(env, meter_id, consumption, meter, quote);
+
+        // Log the payer's activity in the linked UserManagement. A failure there (e.g.
+        // a suspended user) panics and reverts the whole payment, keeping both in sync.
+        // Paying before the due date also earns the configured reputation bonus.
+        if let Some(user_mgmt) = Self::get_user_management_contract(env.clone()) {
+            env.invoke_contract::<()>(
+                &user_mgmt,
+                &Symbol::new(env, "log_activity"),
+                vec![env, from.into_val(env), symbol_short!("PAYMENT").into_val(env)],
+            );
+
+            let (_, on_time_bonus) = Self::get_reputation_policy(env.clone());
+            if paid_on_time && on_time_bonus > 0 {
+                Self::adjust_customer_reputation(env, &user_mgmt, &customer, on_time_bonus as i32);
             }
         }
 
-        // 7. Apply taxes
+        Ok(if is_prepaid { 0 } else { tendered })
+    }
+
+    // Store the billing record for a paid multi-utility bill, credit the provider with
+    // the bill less the processing fee the contract keeps, and count the transaction
+    fn settle_multi_utility_bill(
+        env: &Env,
+        meter_id: &String,
+        consumption: i128,
+        meter: UtilityMeter,
+        quote: &BillQuote,
+    ) {
+        let billing_key = format!("{}_{}", meter_id, env.ledger().timestamp());
+        let billing_data = (
+            consumption,
+            quote.base_amount + quote.overage_amount,
+            quote.tax_amount,
+            quote.fee_amount,
+            quote.final_amount,
+            meter.utility_type.to_u8(),
+            quote.config_version,
+            quote.discount_amount,
+        );
+        env.storage().persistent().set(&billing_key, &billing_data);
+        Self::record_billing_history(env, meter_id, env.ledger().timestamp());
+        Self::add_to_total_paid(env, meter_id, quote.final_amount);
+        Self::record_last_payment(env, meter_id, quote.final_amount);
+        MultiUtilityManager::record_consumption(env, meter_id, consumption);
+
+        Self::credit_provider(env, &meter.provider_id, quote.final_amount - quote.fee_amount);
+
+        env.events().publish(
+            (symbol_short!("BILL_PAID"), meter_id.clone()),
+            (
+                quote.final_amount,
+                meter.utility_type.to_string(),
+                env.ledger().timestamp(),
+                (quote.base_amount, quote.tax_amount, quote.fee_amount, quote.final_amount),
+            ),
+        );
+
+        let mut providers = env
+            .storage()
+            .persistent()
+            .get::<String, soroban_sdk::Map<String, multi_utility::UtilityProvider>>(
+                &multi_utility::UTILITY_PROVIDERS,
+            )
+            .unwrap_or_else(|| soroban_sdk::Map::new(env));
+
+        if let Some(mut provider) = providers.get(meter.provider_id.clone()) {
+            provider.total_transactions += 1;
+            providers.set(meter.provider_id, provider);
+            env.storage()
+                .persistent()
+                .set(&multi_utility::UTILITY_PROVIDERS, &providers);
+        }
+    }
+
+    fn quote_with_config(
+        env: &Env,
+        meter: &UtilityMeter,
+        config: &UtilityConfig,
+        consumption: i128,
+        currency: &String,
+        apply_fees: bool,
+        pricing_window: Option<(u64, u64)>,
+    ) -> Result<BillQuote, BillingError> {
+        // 1. Calculate base amount with tier and time-of-use pricing, plus the standing
+        // charge which is taxed like usage. Usage beyond a data cap is billed separately.
+        let (included, overage) = MultiUtilityManager::split_data_cap(config, consumption);
+        let base_amount =
+            Self::calculate_base_amount(env, config, included, pricing_window) + config.standing_charge;
+        let overage_amount =
+            MultiUtilityManager::charge_for_consumption(config, overage, config.overage_rate);
+
+        // Config discounts the customer qualifies for come off before tax
+        let discount_amount =
+            Self::qualifying_discount(env, meter, config, base_amount + overage_amount);
+        let taxable_amount = base_amount + overage_amount - discount_amount;
+
+        // 2. Apply taxes (config taxes plus regional defaults, in their configured order).
+        // Simple taxes are charged on the taxable amount; compound taxes on the running
+        // subtotal including every tax applied before them. Each is capped at max_amount.
         let mut tax_amount = 0i128;
-        for tax in config.tax_rates.iter() {
-            let tax_calc = (base_amount * tax.rate_percentage) / 100;
+        let tax_rates = MultiUtilityManager::get_effective_tax_rates(env.clone(), config.clone());
+        for tax in tax_rates.iter() {
+            let tax_base = if tax.is_compound { taxable_amount + tax_amount } else { taxable_amount };
+            let mut tax_calc = (tax_base * tax.rate_percentage) / 100;
+            if let Some(max_amount) = tax.max_amount {
+                tax_calc = tax_calc.min(max_amount);
+            }
             tax_amount += tax_calc;
         }
 
-        // 8. Apply fees if requested
+        // Paying after the due date adds the config's late fee on the taxed bill
+        let late_fee_amount = Self::late_fee_for(env, &meter.meter_id, config, taxable_amount + tax_amount);
+
+        // 3. Apply the provider's active fees for this utility if requested: flat fees
+        // as-is, percentage fees on the taxed bill
         let mut fee_amount = 0i128;
         if apply_fees {
-            let fees_key = format!("{}_{}", meter.provider_id, meter.utility_type.to_u8());
-            // In a real implementation, we'd query fees by provider and utility type
-            // For now, we'll use a default processing fee
-            fee_amount = 1000000; // 0.001 XLM default processing fee
+            let fees = MultiUtilityManager::get_active_fees_for(
+                env.clone(),
+                meter.provider_id.clone(),
+                meter.utility_type.clone(),
+            );
+            for fee in fees.iter() {
+                fee_amount += if fee.is_percentage {
+                    ((taxable_amount + tax_amount) * fee.fee_percentage.unwrap_or(0)) / 10000
+                } else {
+                    fee.fee_amount
+                };
+            }
+        }
+
+        // Never let fees exceed the configured share of the base amount
+        if config.max_fee_ratio_bps > 0 {
+            let max_fee = (base_amount * config.max_fee_ratio_bps as i128) / 10000;
+            if fee_amount > max_fee {
+                env.events().publish(
+                    (Symbol::new(env, "FEE_CLAMPED"), meter.meter_id.clone()),
+                    (fee_amount, max_fee),
+                );
+                fee_amount = max_fee;
+            }
         }
 
-        // 9. Calculate final amount
-        let subtotal = base_amount + tax_amount + fee_amount;
+        // 4. Calculate final amount
+        let subtotal = taxable_amount + tax_amount + late_fee_amount + fee_amount;
 
-        // 10. Apply currency conversion if needed
+        // 5. Apply currency conversion if needed
         let mut final_amount = subtotal;
-        if config.currency != currency {
-            let exchange_rate_id = format!("{}_{}", config.currency, currency);
-            let price_feed = OracleManager::get_price_feed(env.clone(), exchange_rate_id)
-                .ok_or("Exchange rate not available")?;
+        let (mut exchange_rate, mut rate_decimals) = (1i128, 0u32);
+        if &config.currency != currency {
+            let (rate, decimals) = Self::conversion_rate(env, &config.currency, currency)?;
+
+            // The conversion scales by the feed's own precision, so a feed quoted at a
+            // different one than the config still bills correctly; flag it for the operator
+            // (check_decimal_consistency) rather than refusing the payment
+            if decimals != config.decimals {
+                env.events().publish(
+                    (Symbol::new(env, "DECIMALS_MISMATCH"), meter.meter_id.clone()),
+                    (config.decimals, decimals),
+                );
+            }
 
-            final_amount = (subtotal * price_feed.price) / (10_i128.pow(price_feed.decimals));
+            final_amount = safe_convert(subtotal, rate, decimals)
+                .map_err(|_| BillingError::ConversionOverflow)?;
+            exchange_rate = rate;
+            rate_decimals = decimals;
         }
 
-        // 11. Validate payment limits
+        // 6. Validate payment limits
         if final_amount < config.minimum_payment {
-            return Err("Amount below minimum payment".to_string());
+            return Err(BillingError::AmountTooLow);
         }
         if final_amount > config.maximum_payment {
-            return Err("Amount exceeds maximum payment".to_string());
+            return Err(BillingError::AmountTooHigh);
         }
 
-        // 12. Process payment
-        let token_client = token::Client::new(&env, &token_address);
-        token_client.transfer(&from, &env.current_contract_address(), &final_amount);
-
-        // 13. Update meter record with detailed billing information
-        let billing_key = format!("{}_{}", meter_id, env.ledger().timestamp());
-        let billing_data = (
-            consumption,
+        Ok(BillQuote {
             base_amount,
+            overage_amount,
+            discount_amount,
             tax_amount,
+            late_fee_amount,
+            export_credit: 0,
             fee_amount,
+            promo_discount: 0,
             final_amount,
-            meter.utility_type.to_u8(),
-            config.version,
-        );
-        env.storage().persistent().set(&billing_key, &billing_data);
+            config_version: config.version,
+            paid_currency: currency.clone(),
+            settled_amount: subtotal,
+            settled_currency: config.currency.clone(),
+            exchange_rate,
+            rate_decimals,
+        })
+    }
 
-        // 14. Update provider transaction count
-        let mut providers = env
+    // Price a bill from the oracle utility rate for the meter's type and region.
+    // Only used when no config matches and the oracle config enables fallback; the
+    // simple bill carries no taxes or fees and is recorded against config version 0.
+    fn quote_with_oracle_rate(
+        env: &Env,
+        meter_id: &String,
+        consumption: i128,
+        currency: &String,
+    ) -> Result<(UtilityMeter, BillQuote), BillingError> {
+        let oracle_config: OracleConfig = env
             .storage()
-            .persistent()
-            .get::<String, soroban_sdk::Map<String, multi_utility::UtilityProvider>>(
-                &multi_utility::UTILITY_PROVIDERS,
-            )
-            .unwrap_or_else(|| soroban_sdk::Map::new(&env));
+            .instance()
+            .get(&symbol_short!("OR_CONF"))
+            .ok_or(BillingError::ConfigNotFound)?;
 
-        if let Some(mut provider) = providers.get(meter.provider_id.clone()) {
-            provider.total_transactions += 1;
-            providers.set(meter.provider_id, provider);
-            env.storage()
-                .persistent()
-                .set(&multi_utility::UTILITY_PROVIDERS, &providers);
+        if !oracle_config.fallback_enabled {
+            return Err(BillingError::ConfigNotFound);
         }
 
-        Ok(())
-    }
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        let provider = MultiUtilityManager::get_provider(env.clone(), meter.provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
 
-    // Get utility provider
-    pub fn get_utility_provider(env: Env, provider_id: String) -> Option<UtilityProvider> {
-        MultiUtilityManager::get_provider(env, provider_id)
+        let utility_type = meter.utility_type.to_string();
+        let rate_id = format!("{}_{}", utility_type, provider.region);
+        let utility_rate = OracleManager::get_utility_rate(env.clone(), rate_id)
+            .ok_or(BillingError::ConfigNotFound)?;
+
+        if utility_rate.reliability_score < oracle_config.min_reliability_score {
+            return Err(BillingError::ReliabilityTooLow);
+        }
+        if env.ledger().timestamp() > utility_rate.last_updated + oracle_config.max_age_seconds {
+            return Err(BillingError::RateUnavailable);
+        }
+
+        let rate_per_kwh = OracleManager::get_aggregated_utility_rate(env.clone(), utility_type, provider.region)
+            .unwrap_or(utility_rate.rate_per_kwh);
+        let base_amount = consumption
+            .checked_mul(rate_per_kwh)
+            .ok_or(BillingError::ConversionOverflow)?;
+
+        let mut final_amount = base_amount;
+        let (mut exchange_rate, mut rate_decimals) = (1i128, 0u32);
+        if &utility_rate.currency != currency {
+            let (rate, decimals) = Self::conversion_rate(env, &utility_rate.currency, currency)?;
+            final_amount = safe_convert(base_amount, rate, decimals)
+                .map_err(|_| BillingError::ConversionOverflow)?;
+            exchange_rate = rate;
+            rate_decimals = decimals;
+        }
+
+        if final_amount <= 0 {
+            return Err(BillingError::AmountTooLow);
+        }
+
+        let quote = BillQuote {
+            base_amount,
+            overage_amount: 0,
+            discount_amount: 0,
+            tax_amount: 0,
+            late_fee_amount: 0,
+            export_credit: 0,
+            fee_amount: 0,
+            promo_discount: 0,
+            final_amount,
+            config_version: 0,
+            paid_currency: currency.clone(),
+            settled_amount: base_amount,
+            settled_currency: utility_rate.currency,
+            exchange_rate,
+            rate_decimals,
+        };
+        Ok((meter, quote))
     }
 
-    // Get utility configuration
-    pub fn get_utility_configuration(env: Env, config_id: String) -> Option<UtilityConfig> {
-        MultiUtilityManager::get_utility_config(env, config_id)
+    // Base charge for a consumption after tier, time-of-use and volume pricing, before taxes and fees
+    fn calculate_base_amount(
+        env: &Env,
+        config: &UtilityConfig,
+        consumption: i128,
+        pricing_window: Option<(u64, u64)>,
+    ) -> i128 {
+        // Calculate base amount (consumption is scaled by config.consumption_decimals)
+        let mut base_amount =
+            MultiUtilityManager::charge_for_consumption(config, consumption, config.base_rate);
+
+        // Apply tier rates if applicable (tier bounds are in whole units)
+        match config.tiered_mode {
+            TierMode::Flat => {
+                let units = MultiUtilityManager::normalize_consumption(config, consumption);
+                for tier_rate in config.tier_rates.iter() {
+                    if units >= tier_rate.min_units && units <= tier_rate.max_units {
+                        base_amount = MultiUtilityManager::charge_for_consumption(
+                            config,
+                            consumption,
+                            tier_rate.rate_per_unit,
+                        );
+                        break;
+                    }
+                }
+            }
+            TierMode::Progressive => {
+                base_amount = MultiUtilityManager::progressive_tier_charge(config, consumption);
+            }
+        }
+
+        // Apply time-of-use rates if applicable, at the current time unless a window is given
+        let now = env.ledger().timestamp();
+        let (start, end) = pricing_window.unwrap_or((now, now));
+        base_amount = (base_amount * MultiUtilityManager::time_of_use_multiplier(config, start, end)) / 100;
+
+        // Apply every seasonal adjustment covering the current month, in declaration order
+        let current_month = MultiUtilityManager::month_of(env.ledger().timestamp());
+        for adjustment in config.seasonal_adjustments.iter() {
+            if MultiUtilityManager::season_covers(&adjustment, current_month) {
+                base_amount = (base_amount * adjustment.rate_adjustment) / 100;
+            }
+        }
+
+        // Apply volume discounts to the marginal cost above each threshold
+        base_amount -= MultiUtilityManager::volume_discount_amount(config, consumption, base_amount);
+
+        // Never charge less than the config's minimum, however low the usage
+        base_amount.max(config.minimum_charge)
     }
 
-    // Get utility meter
-    pub fn get_utility_meter_info(env: Env, meter_id: String) -> Option<UtilityMeter> {
-        MultiUtilityManager::get_meter(env, meter_id)
+    // An explicit currency wins; otherwise fall back to the contract default
+    fn resolve_currency(env: &Env, currency: Option<String>) -> Result<String, BillingError> {
+        match currency {
+            Some(currency) => Ok(currency),
+            None => Self::get_default_currency(env.clone()).ok_or(BillingError::CurrencyNotSet),
+        }
     }
 
-    // Get utility fee
-    pub fn get_utility_fee_info(env: Env, fee_id: String) -> Option<UtilityFee> {
-        MultiUtilityManager::get_utility_fee(env, fee_id)
+    // Require auth from the billing admin
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), BillingError> {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&BILLING_ADMIN)
+            .ok_or(BillingError::NotInitialized)?;
+
+        if &stored_admin != admin {
+            return Err(BillingError::Unauthorized);
+        }
+        Ok(())
     }
 
-    // List providers by type and region
-    pub fn list_providers(
-        env: Env,
-        utility_type: u8,
-        region: String,
-    ) -> Result<Vec<UtilityProvider>, String> {
-        MultiUtilityManager::list_providers_by_type_and_region(env, utility_type, region)
+    // Exchange-rate feed used for currency conversion. With aggregated pricing on, the
+    // feed's price is replaced by the median of its fresh, reliable sources when any qualify.
+    fn conversion_feed(env: &Env, feed_id: String) -> Option<PriceFeed> {
+        let mut feed = OracleManager::get_price_feed(env.clone(), feed_id.clone())?;
+        if Self::get_aggregated_pricing(env.clone()) {
+            if let Some(price) = OracleManager::get_aggregated_price(env.clone(), feed_id) {
+                feed.price = price;
+            }
+        }
+        Some(feed)
     }
 
-    // Update provider status
-    pub fn update_provider_status(
-        env: Env,
-        admin: Address,
-        provider_id: String,
-        is_active: bool,
-    ) -> Result<(), String> {
-        MultiUtilityManager::update_provider_status(env, admin, provider_id, is_active)
+    // Rate converting bills between currencies, directly or through the oracle's base
+    // currency, honouring the aggregated pricing setting
+    fn conversion_rate(env: &Env, from: &String, to: &String) -> Result<(i128, u32), BillingError> {
+        OracleManager::get_conversion_rate(
+            env.clone(),
+            from.clone(),
+            to.clone(),
+            Self::get_aggregated_pricing(env.clone()),
+        )
+        .map_err(|_| BillingError::ExchangeRateUnavailable)
     }
 
-    // Upgrade utility configuration
-    pub fn upgrade_utility_configuration(
-        env: Env,
-        admin: Address,
-        config_id: String,
-        new_config: UtilityConfig,
-    ) -> Result<(), String> {
-        MultiUtilityManager::upgrade_utility_config(env, admin, config_id, new_config)
+    // Payments are refused while the circuit breaker is engaged
+    fn when_not_paused(env: &Env) -> Result<(), BillingError> {
+        if Self::is_paused(env.clone()) {
+            return Err(BillingError::ContractPaused);
+        }
+        Ok(())
     }
 
-    // Validate utility type
-    pub fn validate_utility_type(env: Env, utility_type: u8) -> Result<(), String> {
-        MultiUtilityManager::validate_utility_type(env, utility_type)
+    // Require auth from either the owning party or a registered keeper
+    fn require_owner_or_keeper(env: &Env, caller: &Address, owner: &Address) -> Result<(), BillingError> {
+        caller.require_auth();
+
+        if caller == owner || Self::is_keeper(env.clone(), caller.clone()) {
+            Ok(())
+        } else {
+            Err(BillingError::NotOwnerOrKeeper)
+        }
     }
 
-    // Get all utility types
-    pub fn get_supported_utility_types(env: Env) -> soroban_sdk::Map<u8, String> {
-        MultiUtilityManager::get_utility_types(env)
+    // Count a payment against the meter's spending cap, if one is set. The cycle
+    // anchor rolls forward by whole cycles so spend resets at each cycle boundary.
+    fn apply_spending_cap(env: &Env, meter_id: &String, amount: i128) -> Result<(), BillingError> {
+        if let Some(updated) = Self::check_spending_cap(env, meter_id, amount)? {
+            let cap_key = format!("{}_cap", meter_id);
+            env.storage().persistent().set(&cap_key, &updated);
+        }
+        Ok(())
     }
 
-    // === UPGRADE MANAGEMENT FUNCTIONS ===
+    // Cap state (cap, anchor, spent) after charging `amount`, or None if the meter has no cap
+    fn check_spending_cap(
+        env: &Env,
+        meter_id: &String,
+        amount: i128,
+    ) -> Result<Option<(i128, u64, i128)>, BillingError> {
+        let (cap, mut anchor, mut spent) =
+            match Self::get_spending_cap(env.clone(), meter_id.clone()) {
+                Some(cap) => cap,
+                None => return Ok(None),
+            };
+
+        let now = env.ledger().timestamp();
+        if now >= anchor + SPENDING_CAP_CYCLE_SECS {
+            let elapsed_cycles = (now - anchor) / SPENDING_CAP_CYCLE_SECS;
+            anchor += elapsed_cycles * SPENDING_CAP_CYCLE_SECS;
+            spent = 0;
+        }
 
-    // Initialize upgrade systems
-    pub fn initialize_upgrade_system(env: Env, admin: Address) {
-        UpgradeProxy::initialize(env.clone(), admin.clone());
-        VersionManager::initialize(env.clone(), admin.clone());
-        DataMigration::initialize(env, admin);
+        if spent + amount > cap {
+            return Err(BillingError::SpendingCapExceeded);
+        }
+        Ok(Some((cap, anchor, spent + amount)))
     }
 
-    // Upgrade contract to new version
-    pub fn upgrade_contract(
-        env: Env,
-        admin: Address,
-        new_implementation: Address,
-        new_version: u32,
-    ) -> Result<(), Symbol> {
-        // Check if upgrade is safe
-        let current_version = UpgradeProxy::get_version(env.clone());
-        let is_safe = VersionManager::is_upgrade_safe(env.clone(), current_version, new_version)?;
-        
-        if !is_safe {
-            return Err(Symbol::short("UNSAFE_UPGRADE"));
+    // The promo `customer` may redeem now. Codes that can't be used yield None, or an
+    // error in strict mode.
+    fn usable_promo(env: &Env, customer: &Address, code: &Symbol) -> Result<Option<PromoCode>, BillingError> {
+        let used: Map<(Symbol, Address), bool> = env
+            .storage()
+            .persistent()
+            .get(&PROMO_USED)
+            .unwrap_or_else(|| Map::new(env));
+        let already_used = used.get((code.clone(), customer.clone())).unwrap_or(false);
+
+        let promo = Self::get_promo(env.clone(), code.clone()).filter(|promo| {
+            promo.remaining_uses > 0 && env.ledger().timestamp() < promo.expires_at && !already_used
+        });
+        if promo.is_none() {
+            let strict: bool = env.storage().instance().get(&PROMO_STRICT).unwrap_or(false);
+            if strict {
+                return Err(BillingError::PromoNotApplicable);
+            }
         }
+        Ok(promo)
+    }
 
-        // Backup data before upgrade
-        DataMigration::backup_data(env.clone(), admin.clone())?;
+    // Late fee for paying `balance` now, counting whole days past the billing cycle and
+    // grace period since the meter's last payment. Non-compounding configs charge the
+    // flat fee plus `percentage_fee` bps of the balance once; compounding ones accrue
+    // `percentage_fee` bps per day overdue, for at most MAX_COMPOUND_LATE_FEE_DAYS days and
+    // saturating rather than overflowing. Capped at `max_fee` when set.
+    fn late_fee_for(env: &Env, meter_id: &String, config: &UtilityConfig, balance: i128) -> i128 {
+        let due = match Self::get_last_payment(env.clone(), meter_id.clone()) {
+            Some((last_paid, _)) => {
+                last_paid + (config.billing_cycle_days + config.grace_period_days) as u64 * 86400
+            }
+            None => return 0,
+        };
+        let now = env.ledger().timestamp();
+        if now <= due {
+            return 0;
+        }
+        let days_overdue = (now - due) / 86400;
+        if days_overdue == 0 {
+            return 0;
+        }
 
-        // Execute upgrade
-        UpgradeProxy::upgrade(env.clone(), admin.clone(), new_implementation, new_version)?;
+        let late_fee = &config.late_fee_config;
+        let mut fee = if late_fee.compound_daily {
+            let mut owed = balance;
+            for _ in 0..days_overdue.min(MAX_COMPOUND_LATE_FEE_DAYS) {
+                let interest = owed
+                    .checked_mul(late_fee.percentage_fee)
+                    .map(|accrued| accrued / 10000)
+                    .unwrap_or(i128::MAX);
+                owed = owed.saturating_add(interest);
+                if owed == i128::MAX || (late_fee.max_fee > 0 && owed - balance >= late_fee.max_fee) {
+                    break;
+                }
+            }
+            owed.saturating_sub(balance)
+        } else {
+            late_fee.flat_fee + (balance * late_fee.percentage_fee) / 10000
+        };
+        if late_fee.max_fee > 0 {
+            fee = fee.min(late_fee.max_fee);
+        }
+        fee
+    }
 
-        // Execute data migration if needed
-        let version_info = VersionManager::get_version_info(env.clone(), new_version);
-        if let Some(info) = version_info {
-            if info.migration_required {
-                DataMigration::execute_migration(env.clone(), admin, current_version, new_version)?;
+    // Total of the active, unexpired config discounts that apply to this bill, as a
+    // percentage of `amount` and never more than it. An early-payment discount applies
+    // when paying within the billing cycle that started at the meter's last payment.
+    fn qualifying_discount(env: &Env, meter: &UtilityMeter, config: &UtilityConfig, amount: i128) -> i128 {
+        let now = env.ledger().timestamp();
+        let eligible = Self::get_discount_eligibility(env.clone(), meter.customer_address.clone());
+        let early_payment = Self::get_last_payment(env.clone(), meter.meter_id.clone())
+            .map(|(last_paid, _)| now <= last_paid + config.billing_cycle_days as u64 * 86400)
+            .unwrap_or(false);
+
+        let mut discount = 0i128;
+        for rate in config.discount_rates.iter() {
+            if !rate.is_active || rate.expiry_date.map(|expiry| now > expiry).unwrap_or(false) {
+                continue;
+            }
+            let qualifies = if rate.condition == String::from_str(env, "early_payment") {
+                early_payment
+            } else {
+                eligible.contains(&rate.condition)
+            };
+            if qualifies {
+                discount += (amount * rate.discount_percentage) / 100;
             }
         }
+        discount.min(amount)
+    }
 
-        Ok(())
+    // Consume one use of a promo code already checked with usable_promo
+    fn redeem_promo(env: &Env, customer: &Address, code: &Symbol, discount: i128) {
+        let mut promos: Map<Symbol, PromoCode> = env.storage().persistent().get(&PROMOS).unwrap();
+        let mut promo = promos.get(code.clone()).unwrap();
+        promo.remaining_uses -= 1;
+        promos.set(code.clone(), promo);
+        env.storage().persistent().set(&PROMOS, &promos);
+
+        let mut used: Map<(Symbol, Address), bool> = env
+            .storage()
+            .persistent()
+            .get(&PROMO_USED)
+            .unwrap_or_else(|| Map::new(env));
+        used.set((code.clone(), customer.clone()), true);
+        env.storage().persistent().set(&PROMO_USED, &used);
+
+        env.events()
+            .publish((symbol_short!("PROMO"), code.clone()), (customer.clone(), discount));
     }
 
-    // Register new contract version
-    pub fn register_contract_version(
-        env: Env,
-        admin: Address,
-        version: u32,
-        implementation_address: Address,
-        migration_required: bool,
-        backward_compatible: bool,
-    ) -> Result<(), Symbol> {
-        VersionManager::register_version(
+    // Offset an electricity bill's energy charges (not its fees) with the customer's solar
+    // export credit, returning the credit left to carry forward if any was used. Export
+    // credit is valued in the solar config's currency, which is assumed to match the
+    // electricity provider's.
+    fn apply_solar_export_credit(env: &Env, meter: &UtilityMeter, quote: &mut BillQuote) -> Option<i128> {
+        if meter.utility_type != UtilityType::Electricity {
+            return None;
+        }
+        let credit = Self::get_solar_credit(env.clone(), meter.customer_address.clone());
+        let offset = credit.min(quote.settled_amount - quote.fee_amount).max(0);
+        if offset == 0 {
+            return None;
+        }
+
+        quote.export_credit = offset;
+        quote.settled_amount -= offset;
+        quote.final_amount = quote.settled_amount * quote.exchange_rate / 10_i128.pow(quote.rate_decimals);
+        Some(credit - offset)
+    }
+
+    // Whether paying this meter would draw on value only its customer may spend: a
+    // prepaid balance, meter credit, or solar export credit against electricity bills
+    fn holds_stored_value(env: &Env, meter: &UtilityMeter) -> bool {
+        meter.is_prepaid
+            || Self::get_meter_credit(env.clone(), meter.meter_id.clone()) > 0
+            || (meter.utility_type == UtilityType::Electricity
+                && Self::get_solar_credit(env.clone(), meter.customer_address.clone()) > 0)
+    }
+
+    // BILL_PAID event for the single-amount payment paths: (amount, utility type, timestamp).
+    // The multi-utility path publishes the same topic with its breakdown appended.
+    fn publish_bill_paid(env: &Env, meter_id: &String, amount: i128, utility_type: String) {
+        env.events().publish(
+            (symbol_short!("BILL_PAID"), meter_id.clone()),
+            (amount, utility_type, env.ledger().timestamp()),
+        );
+    }
+
+    // Utility type of a registered meter, or empty for meters only known to pay_bill
+    fn meter_utility_type(env: &Env, meter_id: &String) -> String {
+        MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .map(|meter| meter.utility_type.to_string())
+            .unwrap_or_else(|| String::from_str(env, ""))
+    }
+
+    // Signed reputation change through UserManagement, authorized as this contract
+    fn adjust_customer_reputation(env: &Env, user_mgmt: &Address, customer: &Address, delta: i32) {
+        env.invoke_contract::<()>(
+            user_mgmt,
+            &Symbol::new(env, "adjust_reputation"),
+            vec![
+                env,
+                env.current_contract_address().into_val(env),
+                customer.into_val(env),
+                delta.into_val(env),
+            ],
+        );
+    }
+
+    // Persistent maps the contract can't operate without
+    fn core_storage_keys(env: &Env) -> Vec<Symbol> {
+        vec![
             env,
-            admin,
-            version,
-            implementation_address,
-            migration_required,
-            backward_compatible,
-        )
+            multi_utility::UTILITY_TYPES,
+            multi_utility::UTILITY_PROVIDERS,
+            multi_utility::UTILITY_CONFIGS,
+            multi_utility::UTILITY_METERS,
+            PROVIDER_OWED,
+        ]
     }
 
-    // Get current contract version
-    pub fn get_contract_version(env: Env) -> u32 {
-        UpgradeProxy::get_version(env)
+    fn store_escrow(env: &Env, escrow_id: u64, escrow: &Escrow) {
+        let mut escrows: Map<u64, Escrow> = env
+            .storage()
+            .persistent()
+            .get(&ESCROWS)
+            .unwrap_or_else(|| Map::new(env));
+        escrows.set(escrow_id, escrow.clone());
+        env.storage().persistent().set(&ESCROWS, &escrows);
     }
 
-    // Get contract upgrade info
-    pub fn get_upgrade_info(env: Env) -> (u32, Address, bool) {
-        let version = UpgradeProxy::get_version(env.clone());
-        let implementation = UpgradeProxy::get_implementation(env.clone());
-        let admin = UpgradeProxy::get_admin(env);
-        (version, implementation, admin == env.current_contract_address())
+    // Pay out an unsettled escrow to `to` and mark it settled
+    fn settle_escrow(env: &Env, escrow_id: u64, mut escrow: Escrow, to: &Address) -> Result<(), BillingError> {
+        if escrow.settled {
+            return Err(BillingError::EscrowAlreadySettled);
+        }
+
+        let token_client = token::Client::new(env, &escrow.token);
+        token_client.transfer(&env.current_contract_address(), to, &escrow.amount);
+
+        escrow.settled = true;
+        Self::store_escrow(env, escrow_id, &escrow);
+        Ok(())
     }
 
-    // List all contract versions
-    pub fn list_contract_versions(env: Env) -> soroban_sdk::Map<u32, ContractVersion> {
-        VersionManager::list_versions(env)
+    // Validate a provider refund against one billing record, returning the meter's
+    // provider id and the amount already refunded against the record
+    fn check_record_refund(
+        env: &Env,
+        provider_address: &Address,
+        meter_id: &String,
+        timestamp: u64,
+        amount: i128,
+    ) -> Result<(String, i128), BillingError> {
+        provider_address.require_auth();
+
+        let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        let provider = MultiUtilityManager::get_provider(env.clone(), meter.provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        if &provider.address != provider_address {
+            return Err(BillingError::UnauthorizedProvider);
+        }
+
+        let billed = Self::read_billed_amount(env, meter_id, timestamp)
+            .ok_or(BillingError::BillingRecordNotFound)?;
+
+        if amount <= 0 {
+            return Err(BillingError::InvalidAmount);
+        }
+
+        let (refunded, _) = Self::get_record_refund_status(env.clone(), meter_id.clone(), timestamp);
+        if refunded + amount > billed {
+            return Err(BillingError::RefundExceedsRecord);
+        }
+        if amount > Self::get_provider_owed(env.clone(), meter.provider_id.clone()) {
+            return Err(BillingError::InsufficientEarnings);
+        }
+
+        Ok((meter.provider_id, refunded))
     }
 
-    // Check if upgrade is available
-    pub fn is_upgrade_available(env: Env) -> bool {
-        let current_version = UpgradeProxy::get_version(env.clone());
-        if let Some(latest_version) = VersionManager::get_latest_version(env) {
-            return latest_version > current_version;
+    fn set_refund_pool(env: &Env, provider_id: &String, token_address: &Address, balance: i128) {
+        let mut pools: Map<(String, Address), i128> = env
+            .storage()
+            .persistent()
+            .get(&REFUND_POOLS)
+            .unwrap_or_else(|| Map::new(env));
+        pools.set((provider_id.clone(), token_address.clone()), balance);
+        env.storage().persistent().set(&REFUND_POOLS, &pools);
+    }
+
+    // Adjust a provider's unwithdrawn earnings and the running total owed
+    fn credit_provider(env: &Env, provider_id: &String, amount: i128) {
+        let mut owed: Map<String, i128> = env
+            .storage()
+            .persistent()
+            .get(&PROVIDER_OWED)
+            .unwrap_or_else(|| Map::new(env));
+        let current = owed.get(provider_id.clone()).unwrap_or(0);
+        owed.set(provider_id.clone(), current + amount);
+        env.storage().persistent().set(&PROVIDER_OWED, &owed);
+
+        let total = Self::get_total_owed_to_providers(env.clone());
+        env.storage().persistent().set(&TOTAL_OWED, &(total + amount));
+    }
+
+    // Read-only UserManagement call that yields None instead of failing
+    fn try_read_user<T: TryFromVal<Env, Val>>(
+        env: &Env,
+        user_mgmt: &Address,
+        function: &str,
+        user: &Address,
+    ) -> Option<T> {
+        match env.try_invoke_contract::<T, soroban_sdk::Error>(
+            user_mgmt,
+            &Symbol::new(env, function),
+            vec![env, user.into_val(env)],
+        ) {
+            Ok(Ok(value)) => Some(value),
+            _ => None,
         }
-        false
     }
 
-    // Get migration status
-    pub fn get_migration_status(env: Env) -> (bool, Option<u32>) {
-        let current_version = UpgradeProxy::get_version(env.clone());
-        let version_info = VersionManager::get_version_info(env, current_version);
-        
-        match version_info {
-            Some(info) => (info.migration_required, Some(info.version)),
-            None => (false, None),
+    // Lifetime total behind get_total_paid; every payment path adds to it, so it isn't
+    // limited by MAX_BILLING_HISTORY like the history index
+    fn add_to_total_paid(env: &Env, meter_id: &String, amount: i128) {
+        let current_total: i128 = env.storage().persistent().get(meter_id).unwrap_or(0);
+        env.storage().persistent().set(meter_id, &(current_total + amount));
+    }
+
+    // Remember the latest payment so callers don't have to scan history
+    fn record_last_payment(env: &Env, meter_id: &String, amount: i128) {
+        let last_payment_key = format!("{}_last_pay", meter_id);
+        env.storage()
+            .persistent()
+            .set(&last_payment_key, &(env.ledger().timestamp(), amount));
+    }
+
+    // Append a billing timestamp to the meter's history index, dropping the oldest beyond
+    // MAX_BILLING_HISTORY. A second bill in the same ledger overwrote the first record, so
+    // its timestamp isn't indexed twice.
+    fn record_billing_history(env: &Env, meter_id: &String, timestamp: u64) {
+        let history_key = format!("{}_history", meter_id);
+        let mut history = Self::read_billing_history(env, meter_id);
+        if history.last() == Some(timestamp) {
+            return;
+        }
+        history.push_back(timestamp);
+        while history.len() > MAX_BILLING_HISTORY {
+            history.pop_front();
         }
+        env.storage().persistent().set(&history_key, &history);
+    }
+
+    // Timestamps of a meter's multi-utility bills, oldest first
+    fn read_billing_history(env: &Env, meter_id: &String) -> Vec<u64> {
+        let history_key = format!("{}_history", meter_id);
+        env.storage()
+            .persistent()
+            .get(&history_key)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    // Final amount charged by the multi-utility bill recorded at `timestamp`
+    fn read_billed_amount(env: &Env, meter_id: &String, timestamp: u64) -> Option<i128> {
+        let billing_key = format!("{}_{}", meter_id, timestamp);
+        let billing_data: (i128, i128, i128, i128, i128, u8, u32, i128) =
+            env.storage().persistent().get(&billing_key)?;
+        Some(billing_data.4)
+    }
+
+    // Consumption and final amount of the multi-utility bill recorded at `timestamp`
+    fn read_billed_usage(env: &Env, meter_id: &String, timestamp: u64) -> Option<(i128, i128)> {
+        let billing_key = format!("{}_{}", meter_id, timestamp);
+        let billing_data: (i128, i128, i128, i128, i128, u8, u32, i128) =
+            env.storage().persistent().get(&billing_key)?;
+        Some((billing_data.0, billing_data.4))
+    }
+
+    // Prepaid balance held for a meter (stored under `{meter_id}_prepaid`)
+    fn read_prepaid_balance(env: &Env, meter_id: &String) -> i128 {
+        let prepaid_key = format!("{}_prepaid", meter_id);
+        env.storage().persistent().get(&prepaid_key).unwrap_or(0)
     }
 }
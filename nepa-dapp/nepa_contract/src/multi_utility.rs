@@ -1,16 +1,42 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, Address, Env, String, symbol_short, Symbol, Vec, Map, 
+    contract, contractimpl, Address, Bytes, Env, String, symbol_short, Symbol, Vec, Map, 
     storage::Persistent, storage::Instance
 };
+use crate::errors::BillingError;
+use crate::oracle::{OracleManager, MAX_DECIMALS};
 
 // Storage keys for multi-utility system
-const UTILITY_TYPES: Symbol = symbol_short!("UT_TYPES");
-const UTILITY_PROVIDERS: Symbol = symbol_short!("UT_PROVS");
-const UTILITY_CONFIGS: Symbol = symbol_short!("UT_CONF");
-const UTILITY_FEES: Symbol = symbol_short!("UT_FEES");
-const UTILITY_METERS: Symbol = symbol_short!("UT_METERS");
+pub(crate) const UTILITY_TYPES: Symbol = symbol_short!("UT_TYPES");
+pub(crate) const UTILITY_PROVIDERS: Symbol = symbol_short!("UT_PROVS");
+pub(crate) const UTILITY_CONFIGS: Symbol = symbol_short!("UT_CONF");
+pub(crate) const UTILITY_FEES: Symbol = symbol_short!("UT_FEES");
+pub(crate) const UTILITY_METERS: Symbol = symbol_short!("UT_METERS");
 const UTILITY_VERSIONS: Symbol = symbol_short!("UT_VERS");
+const UTILITY_INIT: Symbol = symbol_short!("UT_INIT");
+const DEFAULT_TAXES: Symbol = symbol_short!("UT_DTAX");
+const PROVIDER_FEES: Symbol = symbol_short!("UT_PFEES");
+const PROVIDER_RATINGS: Symbol = symbol_short!("UT_PRATE");
+const PROVIDER_RATED_AT: Symbol = symbol_short!("UT_RATED");
+const METER_DISCONNECTS: Symbol = symbol_short!("UT_DISC");
+const PROVIDER_SEQ: Symbol = symbol_short!("UT_PSEQ");
+const PREFERRED_PROVIDERS: Symbol = symbol_short!("UT_PREF");
+
+// Maximum dependent entries deactivated per cascade call
+const MAX_CASCADE_BATCH: u32 = 50;
+
+// Maximum readings accepted in one batch submission
+const MAX_READINGS_BATCH: u32 = 100;
+
+// Maximum provider ids accepted in one status query
+const MAX_STATUS_BATCH: u32 = 100;
+
+// Maintenance notes kept per meter, and returned per page
+const MAX_METER_NOTES: u32 = 500;
+const MAX_NOTES_PAGE: u32 = 50;
+
+// Rating cycle for customers whose meter has no resolvable config
+const DEFAULT_RATING_CYCLE_DAYS: u32 = 30;
 
 // Utility Type Enumeration
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -27,7 +53,7 @@ pub enum UtilityType {
 }
 
 impl UtilityType {
-    pub fn from_u8(value: u8) -> Result<Self, String> {
+    pub fn from_u8(value: u8) -> Result<Self, BillingError> {
         match value {
             1 => Ok(UtilityType::Electricity),
             2 => Ok(UtilityType::Water),
@@ -37,7 +63,7 @@ impl UtilityType {
             6 => Ok(UtilityType::PropertyTax),
             7 => Ok(UtilityType::Solar),
             8 => Ok(UtilityType::EVCharging),
-            _ => Err("Invalid utility type".to_string()),
+            _ => Err(BillingError::InvalidUtilityType),
         }
     }
 
@@ -70,6 +96,11 @@ impl UtilityType {
             UtilityType::EVCharging => String::from_str(&"kWh"),
         }
     }
+
+    // Whether the utility is billed from metered consumption rather than a flat rate
+    pub fn is_metered(&self) -> bool {
+        !matches!(self, UtilityType::Internet | UtilityType::PropertyTax)
+    }
 }
 
 // Utility Provider Structure
@@ -84,8 +115,10 @@ pub struct UtilityProvider {
     pub registration_date: u64,
     pub license_number: String,
     pub contact_info: String,
-    pub rating: u8, // 1-5 rating
+    pub rating: u8, // 1-5 rating, the rounded average of customer ratings once any exist
     pub total_transactions: u64,
+    pub rating_count: u32,
+    pub rating_total: u32,
 }
 
 // Utility Configuration Structure
@@ -95,9 +128,22 @@ pub struct UtilityConfig {
     pub provider_id: String,
     pub region: String,
     pub base_rate: i128, // Base rate per unit
+    pub standing_charge: i128, // Fixed charge added once per bill regardless of usage
+    pub minimum_charge: i128, // Floor on the usage charge of a bill, applied after tiers; 0 = none
     pub currency: String,
     pub decimals: u32,
+    // Consumption is reported in 10^-consumption_decimals of the billing unit, while rates
+    // are always quoted per whole unit (e.g. 3 lets a Wh meter bill against a per-kWh rate)
+    pub consumption_decimals: u32,
     pub tier_rates: Vec<TierRate>, // Tiered pricing
+    pub tiered_mode: TierMode,
+    // (threshold units, discount bps) ascending by threshold: units above each threshold
+    // get that discount on their marginal cost
+    pub volume_discount: Vec<(i128, i128)>,
+    // Metered internet: whole units included at the base rate, with anything beyond
+    // billed at overage_rate per unit; 0 = uncapped
+    pub data_cap_units: i128,
+    pub overage_rate: i128,
     pub time_of_use_rates: Vec<TimeOfUseRate>, // Time-based pricing
     pub seasonal_adjustments: Vec<SeasonalAdjustment>,
     pub tax_rates: Vec<TaxRate>,
@@ -106,13 +152,23 @@ pub struct UtilityConfig {
     pub payment_methods: Vec<String>, // Accepted payment methods
     pub billing_cycle_days: u32,
     pub grace_period_days: u32,
+    pub reconnection_cooldown_days: u32, // Days a disconnected meter must wait before reconnection
     pub minimum_payment: i128,
     pub maximum_payment: i128,
+    pub max_fee_ratio_bps: u32, // Cap on total fees as a fraction of the base amount; 0 = uncapped
     pub is_active: bool,
     pub version: u32,
     pub last_updated: u64,
 }
 
+// How tier_rates price a bill: Flat charges all units at the rate of the tier the total
+// falls in; Progressive slices consumption across tiers like a block tariff
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TierMode {
+    Flat,
+    Progressive,
+}
+
 // Tier Rate Structure
 #[derive(Clone)]
 pub struct TierRate {
@@ -148,6 +204,7 @@ pub struct TaxRate {
     pub rate_percentage: i128,
     pub is_compound: bool,
     pub max_amount: Option<i128>,
+    pub order: u32, // Application order, lowest first; ties keep insertion order
 }
 
 // Discount Rate Structure
@@ -178,7 +235,7 @@ pub struct UtilityFee {
     pub provider_id: String,
     pub fee_type: FeeType,
     pub fee_amount: i128,
-    pub fee_percentage: Option<i128>,
+    pub fee_percentage: Option<i128>, // Basis points of the bill, used when is_percentage
     pub is_percentage: bool,
     pub description: String,
     pub is_active: bool,
@@ -199,7 +256,7 @@ pub enum FeeType {
 }
 
 impl FeeType {
-    pub fn from_u8(value: u8) -> Result<Self, String> {
+    pub fn from_u8(value: u8) -> Result<Self, BillingError> {
         match value {
             1 => Ok(FeeType::Processing),
             2 => Ok(FeeType::Service),
@@ -209,7 +266,7 @@ impl FeeType {
             6 => Ok(FeeType::Reconnection),
             7 => Ok(FeeType::Inspection),
             8 => Ok(FeeType::Emergency),
-            _ => Err("Invalid fee type".to_string()),
+            _ => Err(BillingError::InvalidFeeType),
         }
     }
 
@@ -230,11 +287,39 @@ pub struct UtilityMeter {
     pub last_reading_date: u64,
     pub is_active: bool,
     pub is_smart_meter: bool,
+    pub is_prepaid: bool, // Bills are deducted from a topped-up balance
     pub location: String,
     pub meter_model: String,
     pub firmware_version: String,
 }
 
+// Per-entry outcome of a batch reading submission
+#[derive(Clone)]
+pub struct ReadingResult {
+    pub meter_id: String,
+    pub accepted: bool,
+    pub consumption: i128, // Delta since the previous reading when accepted
+    pub error_code: u32, // BillingError code when rejected, 0 otherwise
+}
+
+// Provider-authored maintenance log entry for a meter
+#[derive(Clone)]
+pub struct MeterNote {
+    pub author: Address,
+    pub category: Symbol,
+    pub note: String,
+    pub timestamp: u64,
+}
+
+// Authoritative descriptor of a supported utility type
+#[derive(Clone)]
+pub struct UtilityTypeDetails {
+    pub code: u8,
+    pub name: String,
+    pub unit: String,
+    pub is_metered: bool,
+}
+
 // Utility Version Structure for upgrades
 #[derive(Clone)]
 pub struct UtilityVersion {
@@ -275,6 +360,9 @@ impl MultiUtilityManager {
         env.storage().persistent().set(&UTILITY_FEES, &Map::<String, UtilityFee>::new(&env));
         env.storage().persistent().set(&UTILITY_METERS, &Map::<String, UtilityMeter>::new(&env));
         env.storage().persistent().set(&UTILITY_VERSIONS, &Map<String, UtilityVersion>::new(&env));
+        
+        // Mark system as initialized
+        env.storage().instance().set(&UTILITY_INIT, &true);
     }
 
     // Register a new utility provider
@@ -288,8 +376,9 @@ impl MultiUtilityManager {
         region: String,
         license_number: String,
         contact_info: String,
-    ) -> Result<(), String> {
+    ) -> Result<(), BillingError> {
         admin.require_auth();
+        Self::check_initialized(&env)?;
         
         // Validate utility type
         let utility_type_enum = UtilityType::from_u8(utility_type)?;
@@ -301,7 +390,7 @@ impl MultiUtilityManager {
             .unwrap_or_else(|| Map::new(&env));
         
         if providers.contains_key(provider_id.clone()) {
-            return Err("Provider already registered".to_string());
+            return Err(BillingError::ProviderAlreadyRegistered);
         }
         
         // Create new provider
@@ -317,6 +406,8 @@ impl MultiUtilityManager {
             contact_info,
             rating: 5, // Start with neutral rating
             total_transactions: 0,
+            rating_count: 0,
+            rating_total: 0,
         };
         
         // Store provider
@@ -327,6 +418,37 @@ impl MultiUtilityManager {
         Ok(())
     }
 
+    // Register a provider under an id derived from (name, region, utility type,
+    // registration index), so ids never collide. Returns the generated id.
+    pub fn register_provider_auto_id(
+        env: Env,
+        admin: Address,
+        name: String,
+        provider_address: Address,
+        utility_type: u8,
+        region: String,
+        license_number: String,
+        contact_info: String,
+    ) -> Result<String, BillingError> {
+        let index: u32 = env.storage().instance().get(&PROVIDER_SEQ).unwrap_or(0);
+        let provider_id = Self::derive_provider_id(&env, &name, &region, utility_type, index);
+
+        Self::register_provider(
+            env.clone(),
+            admin,
+            provider_id.clone(),
+            name,
+            provider_address,
+            utility_type,
+            region,
+            license_number,
+            contact_info,
+        )?;
+        env.storage().instance().set(&PROVIDER_SEQ, &(index + 1));
+
+        Ok(provider_id)
+    }
+
     // Add utility configuration
     pub fn add_utility_config(
         env: Env,
@@ -342,27 +464,30 @@ impl MultiUtilityManager {
         grace_period_days: u32,
         minimum_payment: i128,
         maximum_payment: i128,
-    ) -> Result<(), String> {
+    ) -> Result<(), BillingError> {
         admin.require_auth();
+        Self::check_initialized(&env)?;
         
-        // Validate utility type
+        // Validate utility type, billing periods and precision
         let utility_type_enum = UtilityType::from_u8(utility_type)?;
+        Self::validate_cycle_periods(billing_cycle_days, grace_period_days)?;
+        Self::validate_decimals(decimals, 0)?;
         
         // Verify provider exists and is active
         let providers: Map<String, UtilityProvider> = env.storage()
             .persistent()
             .get(&UTILITY_PROVIDERS)
-            .ok_or("No providers registered")?;
+            .ok_or(BillingError::NoProvidersRegistered)?;
         
         let provider = providers.get(provider_id.clone())
-            .ok_or("Provider not found")?;
+            .ok_or(BillingError::ProviderNotFound)?;
         
         if !provider.is_active {
-            return Err("Provider is not active".to_string());
+            return Err(BillingError::ProviderNotActive);
         }
         
         if provider.utility_type != utility_type_enum {
-            return Err("Utility type mismatch".to_string());
+            return Err(BillingError::UtilityTypeMismatch);
         }
         
         // Create configuration
@@ -371,9 +496,16 @@ impl MultiUtilityManager {
             provider_id: provider_id.clone(),
             region,
             base_rate,
+            standing_charge: 0,
+            minimum_charge: 0,
             currency,
             decimals,
+            consumption_decimals: 0, // Whole units by default
             tier_rates: Vec::new(&env),
+            tiered_mode: TierMode::Flat,
+            volume_discount: Vec::new(&env),
+            data_cap_units: 0,
+            overage_rate: 0,
             time_of_use_rates: Vec::new(&env),
             seasonal_adjustments: Vec::new(&env),
             tax_rates: Vec::new(&env),
@@ -388,8 +520,10 @@ impl MultiUtilityManager {
             payment_methods: Vec::new(&env),
             billing_cycle_days,
             grace_period_days,
+            reconnection_cooldown_days: 0,
             minimum_payment,
             maximum_payment,
+            max_fee_ratio_bps: 0,
             is_active: true,
             version: 1,
             last_updated: env.ledger().timestamp(),
@@ -419,8 +553,9 @@ impl MultiUtilityManager {
         meter_model: String,
         firmware_version: String,
         is_smart_meter: bool,
-    ) -> Result<(), String> {
+    ) -> Result<(), BillingError> {
         provider_address.require_auth();
+        Self::check_initialized(&env)?;
         
         // Validate utility type
         let utility_type_enum = UtilityType::from_u8(utility_type)?;
@@ -429,17 +564,17 @@ impl MultiUtilityManager {
         let providers: Map<String, UtilityProvider> = env.storage()
             .persistent()
             .get(&UTILITY_PROVIDERS)
-            .ok_or("No providers registered")?;
+            .ok_or(BillingError::NoProvidersRegistered)?;
         
         let provider = providers.get(provider_id.clone())
-            .ok_or("Provider not found")?;
+            .ok_or(BillingError::ProviderNotFound)?;
         
         if provider.address != provider_address {
-            return Err("Unauthorized provider".to_string());
+            return Err(BillingError::UnauthorizedProvider);
         }
         
         if !provider.is_active {
-            return Err("Provider is not active".to_string());
+            return Err(BillingError::ProviderNotActive);
         }
         
         // Check if meter already exists
@@ -449,7 +584,7 @@ impl MultiUtilityManager {
             .unwrap_or_else(|| Map::new(&env));
         
         if meters.contains_key(meter_id.clone()) {
-            return Err("Meter already registered".to_string());
+            return Err(BillingError::MeterAlreadyRegistered);
         }
         
         // Create meter
@@ -463,6 +598,7 @@ impl MultiUtilityManager {
             last_reading_date: env.ledger().timestamp(),
             is_active: true,
             is_smart_meter,
+            is_prepaid: false,
             location,
             meter_model,
             firmware_version,
@@ -476,6 +612,296 @@ impl MultiUtilityManager {
         Ok(())
     }
 
+    // Provider disconnects a meter, starting its reconnection cooldown
+    pub fn disconnect_meter(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+    ) -> Result<(), BillingError> {
+        provider_address.require_auth();
+        Self::check_initialized(&env)?;
+
+        let mut meter = Self::get_provider_meter(&env, &provider_address, &meter_id)?;
+        meter.is_active = false;
+        Self::store_meter(&env, meter);
+
+        let mut disconnects: Map<String, u64> = env.storage()
+            .persistent()
+            .get(&METER_DISCONNECTS)
+            .unwrap_or_else(|| Map::new(&env));
+        disconnects.set(meter_id, env.ledger().timestamp());
+        env.storage().persistent().set(&METER_DISCONNECTS, &disconnects);
+
+        Ok(())
+    }
+
+    // Provider switches a meter between postpaid and prepaid billing
+    pub fn set_meter_prepaid(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        is_prepaid: bool,
+    ) -> Result<(), BillingError> {
+        provider_address.require_auth();
+        Self::check_initialized(&env)?;
+
+        let mut meter = Self::get_provider_meter(&env, &provider_address, &meter_id)?;
+        meter.is_prepaid = is_prepaid;
+        Self::store_meter(&env, meter);
+
+        Ok(())
+    }
+
+    // Provider hands a meter over to a new customer. The last reading is kept as the
+    // handover snapshot and its date reset, so the new customer is only billed for
+    // consumption from now on. Returns the previous customer.
+    pub fn transfer_meter(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        new_customer: Address,
+    ) -> Result<Address, BillingError> {
+        provider_address.require_auth();
+        Self::check_initialized(&env)?;
+
+        let mut meter = Self::get_provider_meter(&env, &provider_address, &meter_id)?;
+        let old_customer = meter.customer_address.clone();
+        meter.customer_address = new_customer.clone();
+        meter.last_reading_date = env.ledger().timestamp();
+        Self::store_meter(&env, meter);
+
+        env.events().publish(
+            (symbol_short!("MTR_XFER"), meter_id),
+            (old_customer.clone(), new_customer),
+        );
+
+        Ok(old_customer)
+    }
+
+    // Provider reconnects a disconnected meter once its config's cooldown has elapsed
+    pub fn reconnect_meter(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+    ) -> Result<(), BillingError> {
+        provider_address.require_auth();
+        Self::check_initialized(&env)?;
+
+        let mut meter = Self::get_provider_meter(&env, &provider_address, &meter_id)?;
+
+        if let Some(eligible_at) = Self::get_reconnection_eligible_at(env.clone(), meter_id.clone()) {
+            if env.ledger().timestamp() < eligible_at {
+                return Err(BillingError::ReconnectionCooldownActive);
+            }
+        }
+
+        meter.is_active = true;
+        Self::store_meter(&env, meter);
+
+        let mut disconnects: Map<String, u64> = env.storage()
+            .persistent()
+            .get(&METER_DISCONNECTS)
+            .unwrap_or_else(|| Map::new(&env));
+        disconnects.remove(meter_id);
+        env.storage().persistent().set(&METER_DISCONNECTS, &disconnects);
+
+        Ok(())
+    }
+
+    // Append a maintenance note to a meter's log. Notes can't be edited or removed.
+    pub fn add_meter_note(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        note: String,
+        category: Symbol,
+    ) -> Result<(), BillingError> {
+        provider_address.require_auth();
+        Self::check_initialized(&env)?;
+        Self::get_provider_meter(&env, &provider_address, &meter_id)?;
+
+        let notes_key = format!("{}_notes", meter_id);
+        let mut notes: Vec<MeterNote> = env.storage()
+            .persistent()
+            .get(&notes_key)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        if notes.len() >= MAX_METER_NOTES {
+            return Err(BillingError::MeterNotesFull);
+        }
+
+        notes.push_back(MeterNote {
+            author: provider_address,
+            category,
+            note,
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().persistent().set(&notes_key, &notes);
+
+        Ok(())
+    }
+
+    // Page of a meter's notes, oldest first, starting at index `start`
+    pub fn get_meter_notes(env: Env, meter_id: String, start: u32, limit: u32) -> Vec<MeterNote> {
+        let notes_key = format!("{}_notes", meter_id);
+        let notes: Vec<MeterNote> = env.storage()
+            .persistent()
+            .get(&notes_key)
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let end = start.saturating_add(limit.min(MAX_NOTES_PAGE)).min(notes.len());
+        if start >= end {
+            return Vec::new(&env);
+        }
+        notes.slice(start..end)
+    }
+
+    // Add billed consumption to the meter's lifetime total. Only payments count: readings
+    // are billed later, so counting them too would add the same units twice.
+    pub fn record_consumption(env: &Env, meter_id: &String, consumption: i128) {
+        let total_key = format!("{}_consumed", meter_id);
+        let total = Self::get_total_consumption(env.clone(), meter_id.clone());
+        env.storage().persistent().set(&total_key, &(total + consumption));
+    }
+
+    // Lifetime consumption billed to a meter
+    pub fn get_total_consumption(env: Env, meter_id: String) -> i128 {
+        let total_key = format!("{}_consumed", meter_id);
+        env.storage().persistent().get(&total_key).unwrap_or(0)
+    }
+
+    // Earliest timestamp a disconnected meter may be reconnected, or None if not disconnected
+    pub fn get_reconnection_eligible_at(env: Env, meter_id: String) -> Option<u64> {
+        let disconnects: Map<String, u64> = env.storage()
+            .persistent()
+            .get(&METER_DISCONNECTS)?;
+        let disconnected_at = disconnects.get(meter_id.clone())?;
+
+        let cooldown_days = Self::get_meter(env.clone(), meter_id)
+            .and_then(|meter| Self::resolve_meter_config(env, &meter))
+            .map(|config| config.reconnection_cooldown_days)
+            .unwrap_or(0);
+
+        Some(disconnected_at + cooldown_days as u64 * 86400)
+    }
+
+    // Submit a batch of cumulative readings (meter_id, reading, timestamp) from a provider's
+    // data concentrator. Each entry is validated independently and reported in the result.
+    pub fn submit_readings_batch(
+        env: Env,
+        provider_address: Address,
+        readings: Vec<(String, i128, u64)>,
+    ) -> Result<Vec<ReadingResult>, BillingError> {
+        provider_address.require_auth();
+        Self::check_initialized(&env)?;
+        
+        if readings.len() > MAX_READINGS_BATCH {
+            return Err(BillingError::BatchTooLarge);
+        }
+        
+        let providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)
+            .ok_or(BillingError::NoProvidersRegistered)?;
+        
+        let mut meters: Map<String, UtilityMeter> = env.storage()
+            .persistent()
+            .get(&UTILITY_METERS)
+            .unwrap_or_else(|| Map::new(&env));
+        
+        let mut results = Vec::new(&env);
+        for (meter_id, reading, timestamp) in readings.iter() {
+            let outcome = Self::apply_reading(&providers, &mut meters, &provider_address, &meter_id, reading, timestamp);
+            let result = match outcome {
+                Ok(consumption) => ReadingResult {
+                    meter_id,
+                    accepted: true,
+                    consumption,
+                    error_code: 0,
+                },
+                Err(error) => ReadingResult {
+                    meter_id,
+                    accepted: false,
+                    consumption: 0,
+                    error_code: error as u32,
+                },
+            };
+            results.push_back(result);
+        }
+        
+        env.storage().persistent().set(&UTILITY_METERS, &meters);
+        
+        Ok(results)
+    }
+
+    // Submit one cumulative reading from the meter's provider, returning the consumption
+    // since the previous reading
+    pub fn submit_meter_reading(
+        env: Env,
+        provider_address: Address,
+        meter_id: String,
+        new_reading: i128,
+        reading_timestamp: u64,
+    ) -> Result<i128, BillingError> {
+        provider_address.require_auth();
+        Self::check_initialized(&env)?;
+        
+        let providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)
+            .ok_or(BillingError::NoProvidersRegistered)?;
+        
+        let mut meters: Map<String, UtilityMeter> = env.storage()
+            .persistent()
+            .get(&UTILITY_METERS)
+            .ok_or(BillingError::MeterNotFound)?;
+        
+        let consumption = Self::apply_reading(&providers, &mut meters, &provider_address, &meter_id, new_reading, reading_timestamp)?;
+        env.storage().persistent().set(&UTILITY_METERS, &meters);
+        
+        Ok(consumption)
+    }
+
+    // Validate one cumulative reading against the meter and update it in place, returning the delta
+    fn apply_reading(
+        providers: &Map<String, UtilityProvider>,
+        meters: &mut Map<String, UtilityMeter>,
+        provider_address: &Address,
+        meter_id: &String,
+        reading: i128,
+        timestamp: u64,
+    ) -> Result<i128, BillingError> {
+        let mut meter = meters.get(meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        
+        let provider = providers.get(meter.provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        
+        if &provider.address != provider_address {
+            return Err(BillingError::UnauthorizedProvider);
+        }
+        
+        if !meter.is_active {
+            return Err(BillingError::MeterNotActive);
+        }
+        
+        // Cumulative readings never go backwards
+        if reading < meter.last_reading {
+            return Err(BillingError::ReadingRollback);
+        }
+        
+        if timestamp < meter.last_reading_date {
+            return Err(BillingError::StaleReading);
+        }
+        
+        let consumption = reading - meter.last_reading;
+        meter.last_reading = reading;
+        meter.last_reading_date = timestamp;
+        meters.set(meter_id.clone(), meter);
+        
+        Ok(consumption)
+    }
+
     // Add utility fee
     pub fn add_utility_fee(
         env: Env,
@@ -488,8 +914,9 @@ impl MultiUtilityManager {
         fee_percentage: Option<i128>,
         is_percentage: bool,
         description: String,
-    ) -> Result<(), String> {
+    ) -> Result<(), BillingError> {
         admin.require_auth();
+        Self::check_initialized(&env)?;
         
         // Validate utility type and fee type
         let utility_type_enum = UtilityType::from_u8(utility_type)?;
@@ -499,16 +926,16 @@ impl MultiUtilityManager {
         let providers: Map<String, UtilityProvider> = env.storage()
             .persistent()
             .get(&UTILITY_PROVIDERS)
-            .ok_or("No providers registered")?;
+            .ok_or(BillingError::NoProvidersRegistered)?;
         
         providers.get(provider_id.clone())
-            .ok_or("Provider not found")?;
+            .ok_or(BillingError::ProviderNotFound)?;
         
         // Create fee
         let fee = UtilityFee {
             fee_id: fee_id.clone(),
             utility_type: utility_type_enum,
-            provider_id,
+            provider_id: provider_id.clone(),
             fee_type: fee_type_enum,
             fee_amount,
             fee_percentage,
@@ -524,114 +951,627 @@ impl MultiUtilityManager {
             .get(&UTILITY_FEES)
             .unwrap_or_else(|| Map::new(&env));
         
+        // Keep the provider fee index in sync when a fee id is reused
+        if let Some(previous) = fees.get(fee_id.clone()) {
+            Self::unindex_provider_fee(&env, &previous.provider_id, &fee_id);
+        }
+        Self::index_provider_fee(&env, &provider_id, &fee_id);
+        
         fees.set(fee_id, fee);
         env.storage().persistent().set(&UTILITY_FEES, &fees);
         
         Ok(())
     }
 
-    // Get utility provider
-    pub fn get_provider(env: Env, provider_id: String) -> Option<UtilityProvider> {
-        let providers: Map<String, UtilityProvider> = env.storage()
+    // Activate or deactivate a fee
+    pub fn update_fee_status(
+        env: Env,
+        admin: Address,
+        fee_id: String,
+        is_active: bool,
+    ) -> Result<(), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
+        
+        let mut fees: Map<String, UtilityFee> = env.storage()
             .persistent()
-            .get(&UTILITY_PROVIDERS)?;
+            .get(&UTILITY_FEES)
+            .ok_or(BillingError::FeeNotFound)?;
         
-        providers.get(provider_id)
+        let mut fee = fees.get(fee_id.clone())
+            .ok_or(BillingError::FeeNotFound)?;
+        
+        fee.is_active = is_active;
+        fees.set(fee_id, fee);
+        env.storage().persistent().set(&UTILITY_FEES, &fees);
+        
+        Ok(())
     }
 
-    // Get utility configuration
-    pub fn get_utility_config(env: Env, config_id: String) -> Option<UtilityConfig> {
-        let configs: Map<String, UtilityConfig> = env.storage()
+    // List the fees configured by a provider
+    pub fn list_provider_fees(env: Env, provider_id: String, active_only: bool) -> Vec<UtilityFee> {
+        let index: Map<String, Vec<String>> = env.storage()
             .persistent()
-            .get(&UTILITY_CONFIGS)?;
+            .get(&PROVIDER_FEES)
+            .unwrap_or_else(|| Map::new(&env));
         
-        configs.get(config_id)
+        let fees: Map<String, UtilityFee> = env.storage()
+            .persistent()
+            .get(&UTILITY_FEES)
+            .unwrap_or_else(|| Map::new(&env));
+        
+        let mut result = Vec::new(&env);
+        for fee_id in index.get(provider_id).unwrap_or_else(|| Vec::new(&env)).iter() {
+            if let Some(fee) = fees.get(fee_id) {
+                if !active_only || fee.is_active {
+                    result.push_back(fee);
+                }
+            }
+        }
+        
+        result
     }
 
-    // Get utility meter
-    pub fn get_meter(env: Env, meter_id: String) -> Option<UtilityMeter> {
-        let meters: Map<String, UtilityMeter> = env.storage()
+    // Active fees a provider charges on bills for one utility type. Fee ids are free-form,
+    // so this goes through the provider's entry in the PROVIDER_FEES index.
+    pub fn get_active_fees_for(env: Env, provider_id: String, utility_type: UtilityType) -> Vec<UtilityFee> {
+        let mut result = Vec::new(&env);
+        for fee in Self::list_provider_fees(env.clone(), provider_id, true).iter() {
+            if fee.utility_type == utility_type {
+                result.push_back(fee);
+            }
+        }
+        result
+    }
+
+    fn index_provider_fee(env: &Env, provider_id: &String, fee_id: &String) {
+        let mut index: Map<String, Vec<String>> = env.storage()
             .persistent()
-            .get(&UTILITY_METERS)?;
+            .get(&PROVIDER_FEES)
+            .unwrap_or_else(|| Map::new(env));
         
-        meters.get(meter_id)
+        let mut fee_ids = index.get(provider_id.clone()).unwrap_or_else(|| Vec::new(env));
+        if !fee_ids.contains(fee_id) {
+            fee_ids.push_back(fee_id.clone());
+        }
+        index.set(provider_id.clone(), fee_ids);
+        env.storage().persistent().set(&PROVIDER_FEES, &index);
     }
 
-    // Get utility fee
-    pub fn get_utility_fee(env: Env, fee_id: String) -> Option<UtilityFee> {
-        let fees: Map<String, UtilityFee> = env.storage()
+    fn unindex_provider_fee(env: &Env, provider_id: &String, fee_id: &String) {
+        let mut index: Map<String, Vec<String>> = env.storage()
             .persistent()
-            .get(&UTILITY_FEES)?;
+            .get(&PROVIDER_FEES)
+            .unwrap_or_else(|| Map::new(env));
         
-        fees.get(fee_id)
+        let mut fee_ids = index.get(provider_id.clone()).unwrap_or_else(|| Vec::new(env));
+        if let Some(position) = fee_ids.first_index_of(fee_id) {
+            fee_ids.remove(position);
+        }
+        index.set(provider_id.clone(), fee_ids);
+        env.storage().persistent().set(&PROVIDER_FEES, &index);
     }
 
-    // List providers by utility type and region
-    pub fn list_providers_by_type_and_region(
+    // Set a default tax applied to every config of this region and utility type
+    pub fn set_default_tax(
         env: Env,
-        utility_type: u8,
+        admin: Address,
         region: String,
-    ) -> Result<Vec<UtilityProvider>, String> {
-        let utility_type_enum = UtilityType::from_u8(utility_type)?;
+        utility_type: u8,
+        tax: TaxRate,
+    ) -> Result<(), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
         
-        let providers: Map<String, UtilityProvider> = env.storage()
+        // Validate utility type
+        UtilityType::from_u8(utility_type)?;
+        
+        let mut default_taxes: Map<String, Vec<TaxRate>> = env.storage()
             .persistent()
-            .get(&UTILITY_PROVIDERS)
-            .ok_or("No providers registered")?;
+            .get(&DEFAULT_TAXES)
+            .unwrap_or_else(|| Map::new(&env));
         
-        let mut result = Vec::new(&env);
+        let key = format!("{}_{}", region, utility_type);
+        let existing = default_taxes.get(key.clone()).unwrap_or_else(|| Vec::new(&env));
         
-        for (_, provider) in providers.iter() {
-            if provider.utility_type == utility_type_enum && 
-               provider.region == region && 
-               provider.is_active {
-                result.push_back(provider);
+        // Replace any default with the same name
+        let mut updated = Vec::new(&env);
+        for existing_tax in existing.iter() {
+            if existing_tax.tax_name != tax.tax_name {
+                updated.push_back(existing_tax);
             }
         }
+        updated.push_back(tax);
         
-        Ok(result)
+        default_taxes.set(key, updated);
+        env.storage().persistent().set(&DEFAULT_TAXES, &default_taxes);
+        
+        Ok(())
     }
 
-    // Update provider status
-    pub fn update_provider_status(
-        env: Env,
-        admin: Address,
-        provider_id: String,
-        is_active: bool,
-    ) -> Result<(), String> {
-        admin.require_auth();
-        
-        let mut providers: Map<String, UtilityProvider> = env.storage()
+    // Get default taxes for a region and utility type
+    pub fn get_default_taxes(env: Env, region: String, utility_type: u8) -> Vec<TaxRate> {
+        let default_taxes: Map<String, Vec<TaxRate>> = env.storage()
             .persistent()
-            .get(&UTILITY_PROVIDERS)
-            .ok_or("No providers registered")?;
+            .get(&DEFAULT_TAXES)
+            .unwrap_or_else(|| Map::new(&env));
         
-        let mut provider = providers.get(provider_id.clone())
-            .ok_or("Provider not found")?;
+        default_taxes
+            .get(format!("{}_{}", region, utility_type))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // Config taxes plus matching defaults; a config tax overrides a default of the same name
+    pub fn get_effective_tax_rates(env: Env, config: UtilityConfig) -> Vec<TaxRate> {
+        let mut taxes = config.tax_rates.clone();
+        let defaults = Self::get_default_taxes(env.clone(), config.region.clone(), config.utility_type.to_u8());
         
-        provider.is_active = is_active;
-        providers.set(provider_id, provider);
-        env.storage().persistent().set(&UTILITY_PROVIDERS, &providers);
+        for default_tax in defaults.iter() {
+            let overridden = config.tax_rates
+                .iter()
+                .any(|tax| tax.tax_name == default_tax.tax_name);
+            if !overridden {
+                taxes.push_back(default_tax);
+            }
+        }
         
-        Ok(())
+        Self::sort_taxes_by_order(&env, taxes)
     }
 
-    // Upgrade utility configuration
-    pub fn upgrade_utility_config(
+    // Get utility provider
+    pub fn get_provider(env: Env, provider_id: String) -> Option<UtilityProvider> {
+        let providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)?;
+        
+        providers.get(provider_id)
+    }
+
+    // Active flag for each requested provider; unknown ids map to None
+    pub fn get_providers_status(
+        env: Env,
+        provider_ids: Vec<String>,
+    ) -> Result<Vec<(String, Option<bool>)>, BillingError> {
+        if provider_ids.len() > MAX_STATUS_BATCH {
+            return Err(BillingError::BatchTooLarge);
+        }
+
+        let providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut statuses = Vec::new(&env);
+        for provider_id in provider_ids.iter() {
+            let status = providers.get(provider_id.clone()).map(|p| p.is_active);
+            statuses.push_back((provider_id, status));
+        }
+        Ok(statuses)
+    }
+
+    // First provider registered with the given address
+    pub fn get_provider_by_address(env: Env, address: Address) -> Option<UtilityProvider> {
+        let providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)?;
+
+        providers.values().iter().find(|provider| provider.address == address)
+    }
+
+    // Get utility configuration
+    pub fn get_utility_config(env: Env, config_id: String) -> Option<UtilityConfig> {
+        let configs: Map<String, UtilityConfig> = env.storage()
+            .persistent()
+            .get(&UTILITY_CONFIGS)?;
+        
+        configs.get(config_id)
+    }
+
+    // Resolve the config that prices a meter: the `{provider_id}_{region}` config of the
+    // meter's provider, which must match both the provider and the meter's utility type.
+    // Active flags are left to the caller so misconfigurations can be told apart.
+    pub fn resolve_meter_config(env: Env, meter: &UtilityMeter) -> Option<UtilityConfig> {
+        let provider = Self::get_provider(env.clone(), meter.provider_id.clone())?;
+        let config_id = format!("{}_{}", meter.provider_id, provider.region);
+        let config = Self::get_utility_config(env, config_id)?;
+
+        if config.provider_id != meter.provider_id || config.utility_type != meter.utility_type {
+            return None;
+        }
+
+        Some(config)
+    }
+
+    // Get utility meter
+    pub fn get_meter(env: Env, meter_id: String) -> Option<UtilityMeter> {
+        let meters: Map<String, UtilityMeter> = env.storage()
+            .persistent()
+            .get(&UTILITY_METERS)?;
+        
+        meters.get(meter_id)
+    }
+
+    // Ids of all meters registered to a customer
+    pub fn get_customer_meter_ids(env: Env, customer: Address) -> Vec<String> {
+        let mut meter_ids = Vec::new(&env);
+        let meters: Option<Map<String, UtilityMeter>> = env.storage()
+            .persistent()
+            .get(&UTILITY_METERS);
+        if let Some(meters) = meters {
+            for (meter_id, meter) in meters.iter() {
+                if meter.customer_address == customer {
+                    meter_ids.push_back(meter_id);
+                }
+            }
+        }
+        meter_ids
+    }
+
+    // All meters registered to a customer
+    pub fn list_meters_by_customer(env: Env, customer: Address) -> Vec<UtilityMeter> {
+        let mut result = Vec::new(&env);
+        let meters: Option<Map<String, UtilityMeter>> = env.storage()
+            .persistent()
+            .get(&UTILITY_METERS);
+        if let Some(meters) = meters {
+            for (_, meter) in meters.iter() {
+                if meter.customer_address == customer {
+                    result.push_back(meter);
+                }
+            }
+        }
+        result
+    }
+
+    // Get utility fee
+    pub fn get_utility_fee(env: Env, fee_id: String) -> Option<UtilityFee> {
+        let fees: Map<String, UtilityFee> = env.storage()
+            .persistent()
+            .get(&UTILITY_FEES)?;
+        
+        fees.get(fee_id)
+    }
+
+    // List providers by utility type and region
+    pub fn list_providers_by_type_and_region(
+        env: Env,
+        utility_type: u8,
+        region: String,
+    ) -> Result<Vec<UtilityProvider>, BillingError> {
+        let utility_type_enum = UtilityType::from_u8(utility_type)?;
+        
+        let providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)
+            .ok_or(BillingError::NoProvidersRegistered)?;
+        
+        let mut result = Vec::new(&env);
+        
+        for (_, provider) in providers.iter() {
+            if provider.utility_type == utility_type_enum && 
+               provider.region == region && 
+               provider.is_active {
+                result.push_back(provider);
+            }
+        }
+        
+        Ok(result)
+    }
+
+    // Update provider status
+    pub fn update_provider_status(
+        env: Env,
+        admin: Address,
+        provider_id: String,
+        is_active: bool,
+    ) -> Result<(), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
+        
+        let mut providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)
+            .ok_or(BillingError::NoProvidersRegistered)?;
+        
+        let mut provider = providers.get(provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        
+        provider.is_active = is_active;
+        providers.set(provider_id, provider);
+        env.storage().persistent().set(&UTILITY_PROVIDERS, &providers);
+        
+        Ok(())
+    }
+
+    // Customer picks their default provider for a utility type. The provider must offer
+    // that type in a region where the customer already has a meter.
+    pub fn set_preferred_provider(
+        env: Env,
+        customer: Address,
+        utility_type: u8,
+        provider_id: String,
+    ) -> Result<(), BillingError> {
+        customer.require_auth();
+        Self::check_initialized(&env)?;
+        Self::validate_utility_type(env.clone(), utility_type)?;
+
+        let provider = Self::get_provider(env.clone(), provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        if !provider.is_active {
+            return Err(BillingError::ProviderNotActive);
+        }
+        if provider.utility_type.to_u8() != utility_type {
+            return Err(BillingError::UtilityTypeMismatch);
+        }
+
+        let serves_customer = Self::get_customer_meter_ids(env.clone(), customer.clone())
+            .iter()
+            .filter_map(|meter_id| Self::get_meter(env.clone(), meter_id))
+            .filter_map(|meter| Self::get_provider(env.clone(), meter.provider_id))
+            .any(|meter_provider| meter_provider.region == provider.region);
+        if !serves_customer {
+            return Err(BillingError::ProviderRegionMismatch);
+        }
+
+        let mut preferences: Map<(Address, u8), String> = env.storage()
+            .persistent()
+            .get(&PREFERRED_PROVIDERS)
+            .unwrap_or_else(|| Map::new(&env));
+        preferences.set((customer, utility_type), provider_id);
+        env.storage().persistent().set(&PREFERRED_PROVIDERS, &preferences);
+
+        Ok(())
+    }
+
+    pub fn get_preferred_provider(env: Env, customer: Address, utility_type: u8) -> Option<String> {
+        let preferences: Map<(Address, u8), String> = env.storage()
+            .persistent()
+            .get(&PREFERRED_PROVIDERS)?;
+        preferences.get((customer, utility_type))
+    }
+
+    // Record a customer's 1-5 star rating of a provider in its per-star counts and its
+    // running average. Only customers with a meter under the provider may rate, once per
+    // billing cycle of that meter.
+    pub fn submit_provider_rating(
+        env: Env,
+        customer: Address,
+        provider_id: String,
+        score: u8,
+    ) -> Result<(), BillingError> {
+        customer.require_auth();
+        Self::check_initialized(&env)?;
+
+        if score < 1 || score > 5 {
+            return Err(BillingError::InvalidRating);
+        }
+
+        let mut providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)
+            .ok_or(BillingError::ProviderNotFound)?;
+        let mut provider = providers.get(provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+
+        let meter = Self::list_meters_by_customer(env.clone(), customer.clone())
+            .iter()
+            .find(|meter| meter.provider_id == provider_id)
+            .ok_or(BillingError::NotProviderCustomer)?;
+        let cycle_days = Self::resolve_meter_config(env.clone(), &meter)
+            .map(|config| config.billing_cycle_days)
+            .unwrap_or(DEFAULT_RATING_CYCLE_DAYS);
+
+        let now = env.ledger().timestamp();
+        let mut rated_at: Map<(Address, String), u64> = env.storage()
+            .persistent()
+            .get(&PROVIDER_RATED_AT)
+            .unwrap_or_else(|| Map::new(&env));
+        if let Some(last) = rated_at.get((customer.clone(), provider_id.clone())) {
+            if now < last + cycle_days as u64 * 86400 {
+                return Err(BillingError::AlreadyRatedThisCycle);
+            }
+        }
+        rated_at.set((customer, provider_id.clone()), now);
+        env.storage().persistent().set(&PROVIDER_RATED_AT, &rated_at);
+
+        provider.rating_count += 1;
+        provider.rating_total += score as u32;
+        provider.rating = ((provider.rating_total * 10 / provider.rating_count + 5) / 10) as u8;
+        providers.set(provider_id.clone(), provider);
+        env.storage().persistent().set(&UTILITY_PROVIDERS, &providers);
+
+        let mut ratings: Map<String, Vec<u32>> = env.storage()
+            .persistent()
+            .get(&PROVIDER_RATINGS)
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut counts = ratings.get(provider_id.clone())
+            .unwrap_or_else(|| Vec::from_array(&env, [0u32; 5]));
+        let bucket = (score - 1) as u32;
+        counts.set(bucket, counts.get(bucket).unwrap_or(0) + 1);
+
+        ratings.set(provider_id, counts);
+        env.storage().persistent().set(&PROVIDER_RATINGS, &ratings);
+
+        Ok(())
+    }
+
+    // Count of 1..5 star ratings received by a provider, index 0 being 1 star
+    pub fn get_rating_distribution(env: Env, provider_id: String) -> [u32; 5] {
+        let mut distribution = [0u32; 5];
+
+        let ratings: Option<Map<String, Vec<u32>>> = env.storage()
+            .persistent()
+            .get(&PROVIDER_RATINGS);
+        if let Some(counts) = ratings.and_then(|ratings| ratings.get(provider_id)) {
+            for (bucket, count) in counts.iter().enumerate().take(5) {
+                distribution[bucket] = count;
+            }
+        }
+
+        distribution
+    }
+
+    // Deactivate a provider and, in bounded batches, all of its configs and meters.
+    // Returns (configs deactivated, meters deactivated, complete); call again until complete.
+    pub fn deactivate_provider_cascade(
+        env: Env,
+        admin: Address,
+        provider_id: String,
+    ) -> Result<(u32, u32, bool), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
+        
+        let mut providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)
+            .ok_or(BillingError::NoProvidersRegistered)?;
+        
+        let mut provider = providers.get(provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+        
+        if provider.is_active {
+            provider.is_active = false;
+            providers.set(provider_id.clone(), provider);
+            env.storage().persistent().set(&UTILITY_PROVIDERS, &providers);
+        }
+        
+        let mut processed = 0u32;
+        let mut complete = true;
+        
+        // Deactivate configs (already-inactive ones are skipped, so reruns resume)
+        let mut configs_deactivated = 0u32;
+        let mut configs: Map<String, UtilityConfig> = env.storage()
+            .persistent()
+            .get(&UTILITY_CONFIGS)
+            .unwrap_or_else(|| Map::new(&env));
+        
+        for (config_id, mut config) in configs.iter() {
+            if config.provider_id != provider_id || !config.is_active {
+                continue;
+            }
+            if processed >= MAX_CASCADE_BATCH {
+                complete = false;
+                break;
+            }
+            config.is_active = false;
+            configs.set(config_id, config);
+            configs_deactivated += 1;
+            processed += 1;
+        }
+        env.storage().persistent().set(&UTILITY_CONFIGS, &configs);
+        
+        // Deactivate meters
+        let mut meters_deactivated = 0u32;
+        let mut meters: Map<String, UtilityMeter> = env.storage()
+            .persistent()
+            .get(&UTILITY_METERS)
+            .unwrap_or_else(|| Map::new(&env));
+        
+        for (meter_id, mut meter) in meters.iter() {
+            if meter.provider_id != provider_id || !meter.is_active {
+                continue;
+            }
+            if processed >= MAX_CASCADE_BATCH {
+                complete = false;
+                break;
+            }
+            meter.is_active = false;
+            meters.set(meter_id, meter);
+            meters_deactivated += 1;
+            processed += 1;
+        }
+        env.storage().persistent().set(&UTILITY_METERS, &meters);
+        
+        // Emit cascade summary
+        env.events().publish(
+            (symbol_short!("PROV_CASC"), provider_id),
+            (configs_deactivated, meters_deactivated, complete),
+        );
+        
+        Ok((configs_deactivated, meters_deactivated, complete))
+    }
+
+    // Remove a provider for good. Refused while any active meter or config still
+    // references it; deactivate those first (e.g. with deactivate_provider_cascade).
+    pub fn deregister_provider(
+        env: Env,
+        admin: Address,
+        provider_id: String,
+    ) -> Result<(), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
+
+        let mut providers: Map<String, UtilityProvider> = env.storage()
+            .persistent()
+            .get(&UTILITY_PROVIDERS)
+            .ok_or(BillingError::NoProvidersRegistered)?;
+        if !providers.contains_key(provider_id.clone()) {
+            return Err(BillingError::ProviderNotFound);
+        }
+
+        let meters: Map<String, UtilityMeter> = env.storage()
+            .persistent()
+            .get(&UTILITY_METERS)
+            .unwrap_or_else(|| Map::new(&env));
+        let configs: Map<String, UtilityConfig> = env.storage()
+            .persistent()
+            .get(&UTILITY_CONFIGS)
+            .unwrap_or_else(|| Map::new(&env));
+        let has_active_meter = meters
+            .values()
+            .iter()
+            .any(|meter| meter.provider_id == provider_id && meter.is_active);
+        let has_active_config = configs
+            .values()
+            .iter()
+            .any(|config| config.provider_id == provider_id && config.is_active);
+        if has_active_meter || has_active_config {
+            return Err(BillingError::ProviderHasActiveMeters);
+        }
+
+        providers.remove(provider_id.clone());
+        env.storage().persistent().set(&UTILITY_PROVIDERS, &providers);
+
+        env.events().publish((symbol_short!("PROV_DREG"), provider_id), admin);
+
+        Ok(())
+    }
+
+    // Upgrade utility configuration
+    pub fn upgrade_utility_config(
         env: Env,
         admin: Address,
         config_id: String,
         new_config: UtilityConfig,
-    ) -> Result<(), String> {
+        allow_currency_change: bool,
+        migration_note: Option<String>,
+    ) -> Result<(), BillingError> {
         admin.require_auth();
+        Self::check_initialized(&env)?;
+        Self::validate_cycle_periods(new_config.billing_cycle_days, new_config.grace_period_days)?;
+        Self::validate_decimals(new_config.decimals, new_config.consumption_decimals)?;
+        Self::validate_volume_discount(&new_config.volume_discount)?;
+        Self::validate_data_cap(new_config.data_cap_units, new_config.overage_rate)?;
+        let tier_rates = Self::validate_tiers(&new_config.tier_rates)?;
+        if new_config.minimum_charge < 0 {
+            return Err(BillingError::InvalidAmount);
+        }
         
         let mut configs: Map<String, UtilityConfig> = env.storage()
             .persistent()
             .get(&UTILITY_CONFIGS)
-            .ok_or("No configurations found")?;
+            .ok_or(BillingError::ConfigNotFound)?;
         
         let old_config = configs.get(config_id.clone())
-            .ok_or("Configuration not found")?;
+            .ok_or(BillingError::ConfigNotFound)?;
+        
+        // Re-denominating a tariff changes the meaning of past balances, so it must be
+        // explicitly allowed and explained by a migration note
+        let currency_changed = new_config.currency != old_config.currency;
+        if currency_changed && (!allow_currency_change || migration_note.is_none()) {
+            return Err(BillingError::CurrencyChangeRequiresMigration);
+        }
         
         // Create version record
         let version = UtilityVersion {
@@ -641,7 +1581,7 @@ impl MultiUtilityManager {
             deployment_date: env.ledger().timestamp(),
             is_active: true,
             migration_required: true,
-            description: String::from_str(&"Configuration upgrade"),
+            description: migration_note.unwrap_or_else(|| String::from_str(&"Configuration upgrade")),
         };
         
         // Store version
@@ -656,6 +1596,7 @@ impl MultiUtilityManager {
         
         // Update configuration
         let mut updated_config = new_config;
+        updated_config.tier_rates = tier_rates;
         updated_config.version = old_config.version + 1;
         updated_config.last_updated = env.ledger().timestamp();
         
@@ -665,17 +1606,467 @@ impl MultiUtilityManager {
         Ok(())
     }
 
+    // Add a tier to a config in place. Unlike upgrade_utility_config this doesn't
+    // create a new version; the tier set must stay contiguous once it's added, and is
+    // stored back in min_units order wherever the new tier lands.
+    pub fn add_tier_rate(
+        env: Env,
+        admin: Address,
+        config_id: String,
+        tier: TierRate,
+    ) -> Result<(), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
+
+        let mut config = Self::get_utility_config(env.clone(), config_id.clone())
+            .ok_or(BillingError::ConfigNotFound)?;
+        config.tier_rates.push_back(tier);
+        config.tier_rates = Self::validate_tiers(&config.tier_rates)?;
+        Self::store_config(&env, config_id, config);
+
+        Ok(())
+    }
+
+    // Append a time-of-use rate to a config in place
+    pub fn add_tou_rate(
+        env: Env,
+        admin: Address,
+        config_id: String,
+        rate: TimeOfUseRate,
+    ) -> Result<(), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
+        Self::validate_tou_rate(&rate)?;
+
+        let mut config = Self::get_utility_config(env.clone(), config_id.clone())
+            .ok_or(BillingError::ConfigNotFound)?;
+        config.time_of_use_rates.push_back(rate);
+        Self::store_config(&env, config_id, config);
+
+        Ok(())
+    }
+
+    // Append a tax to a config in place
+    pub fn add_tax_rate(
+        env: Env,
+        admin: Address,
+        config_id: String,
+        tax: TaxRate,
+    ) -> Result<(), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
+        Self::validate_tax_rate(&tax)?;
+
+        let mut config = Self::get_utility_config(env.clone(), config_id.clone())
+            .ok_or(BillingError::ConfigNotFound)?;
+        config.tax_rates.push_back(tax);
+        Self::store_config(&env, config_id, config);
+
+        Ok(())
+    }
+
+    // Append a discount to a config in place
+    pub fn add_discount_rate(
+        env: Env,
+        admin: Address,
+        config_id: String,
+        discount: DiscountRate,
+    ) -> Result<(), BillingError> {
+        admin.require_auth();
+        Self::check_initialized(&env)?;
+        Self::validate_discount_rate(&env, &discount)?;
+
+        let mut config = Self::get_utility_config(env.clone(), config_id.clone())
+            .ok_or(BillingError::ConfigNotFound)?;
+        config.discount_rates.push_back(discount);
+        Self::store_config(&env, config_id, config);
+
+        Ok(())
+    }
+
+    // Save an edited config under the same version, stamping last_updated
+    fn store_config(env: &Env, config_id: String, mut config: UtilityConfig) {
+        config.last_updated = env.ledger().timestamp();
+        let mut configs: Map<String, UtilityConfig> = env.storage()
+            .persistent()
+            .get(&UTILITY_CONFIGS)
+            .unwrap_or_else(|| Map::new(env));
+        configs.set(config_id, config);
+        env.storage().persistent().set(&UTILITY_CONFIGS, &configs);
+    }
+
+    // Get the version record created when a config was upgraded to `version`
+    pub fn get_config_version(env: Env, config_id: String, version: u32) -> Option<UtilityVersion> {
+        let versions: Map<String, UtilityVersion> = env.storage()
+            .persistent()
+            .get(&UTILITY_VERSIONS)?;
+        
+        versions.get(format!("{}_{}", config_id, version))
+    }
+
+    // List the versions recorded for a config, oldest first
+    pub fn list_config_versions(env: Env, config_id: String) -> Vec<u32> {
+        let mut result = Vec::new(&env);
+        
+        let current_version = match Self::get_utility_config(env.clone(), config_id.clone()) {
+            Some(config) => config.version,
+            None => return result,
+        };
+        
+        let versions: Map<String, UtilityVersion> = env.storage()
+            .persistent()
+            .get(&UTILITY_VERSIONS)
+            .unwrap_or_else(|| Map::new(&env));
+        
+        for version in 1..=current_version {
+            if versions.contains_key(format!("{}_{}", config_id, version)) {
+                result.push_back(version);
+            }
+        }
+        
+        result
+    }
+
+    // Convert reported consumption into whole billing units (truncating)
+    pub fn normalize_consumption(config: &UtilityConfig, consumption: i128) -> i128 {
+        consumption / 10_i128.pow(config.consumption_decimals)
+    }
+
+    // Time-of-use multiplier (100 = 1x) for a timestamp: the first rate covering its hour
+    // and day of week, or 100 when none does
+    pub fn time_of_use_multiplier_at(config: &UtilityConfig, timestamp: u64) -> i128 {
+        let hour = ((timestamp / 3600) % 24) as u8;
+        let day_of_week = ((timestamp / 86400) % 7) as u8;
+
+        for tou_rate in config.time_of_use_rates.iter() {
+            if hour >= tou_rate.start_hour
+                && hour <= tou_rate.end_hour
+                && tou_rate.days_of_week.contains(day_of_week)
+            {
+                return tou_rate.rate_multiplier;
+            }
+        }
+        100
+    }
+
+    // Time-of-use multiplier over [start, end), weighting each hour spanned by the seconds
+    // spent in it. An empty window takes the multiplier at `start`.
+    pub fn time_of_use_multiplier(config: &UtilityConfig, start: u64, end: u64) -> i128 {
+        if end <= start {
+            return Self::time_of_use_multiplier_at(config, start);
+        }
+
+        let mut weighted: i128 = 0;
+        let mut cursor = start;
+        while cursor < end {
+            let hour_end = (cursor / 3600 + 1) * 3600;
+            let slice_end = if hour_end < end { hour_end } else { end };
+            weighted += Self::time_of_use_multiplier_at(config, cursor) * (slice_end - cursor) as i128;
+            cursor = slice_end;
+        }
+        weighted / (end - start) as i128
+    }
+
+    // Charge for reported consumption at a per-whole-unit rate, scaling after multiplying to keep precision
+    pub fn charge_for_consumption(config: &UtilityConfig, consumption: i128, rate_per_unit: i128) -> i128 {
+        (consumption * rate_per_unit) / 10_i128.pow(config.consumption_decimals)
+    }
+
+    // Amount and consumption precision are both bounded to 0..=MAX_DECIMALS
+    fn validate_decimals(decimals: u32, consumption_decimals: u32) -> Result<(), BillingError> {
+        if decimals > MAX_DECIMALS || consumption_decimals > MAX_DECIMALS {
+            return Err(BillingError::InvalidDecimals);
+        }
+        Ok(())
+    }
+
+    // Thresholds must be non-negative and strictly ascending, discounts within 0..=10000 bps
+    fn validate_volume_discount(volume_discount: &Vec<(i128, i128)>) -> Result<(), BillingError> {
+        let mut previous: Option<i128> = None;
+        for (threshold, discount_bps) in volume_discount.iter() {
+            if threshold < 0 || discount_bps < 0 || discount_bps > 10000 {
+                return Err(BillingError::InvalidVolumeDiscount);
+            }
+            if let Some(previous) = previous {
+                if threshold <= previous {
+                    return Err(BillingError::InvalidVolumeDiscount);
+                }
+            }
+            previous = Some(threshold);
+        }
+        Ok(())
+    }
+
+    // Calendar month (1-12, UTC) of a ledger timestamp
+    pub fn month_of(timestamp: u64) -> u8 {
+        // Civil-from-days over 400-year eras, with years starting in March
+        let z = (timestamp / 86400) as i64 + 719468;
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        if mp < 10 { (mp + 3) as u8 } else { (mp - 9) as u8 }
+    }
+
+    // Whether a seasonal window includes `month`; windows with start > end wrap the
+    // year boundary (e.g. November to February)
+    pub fn season_covers(adjustment: &SeasonalAdjustment, month: u8) -> bool {
+        if adjustment.start_month <= adjustment.end_month {
+            month >= adjustment.start_month && month <= adjustment.end_month
+        } else {
+            month >= adjustment.start_month || month <= adjustment.end_month
+        }
+    }
+
+    // Block-tariff charge: tiers are taken in min_units order (validate_tiers stores them
+    // that way), each covering the units above the previous tier's max_units up to its
+    // own. Units below the first tier's min_units or beyond the last tier pay the base rate.
+    pub fn progressive_tier_charge(config: &UtilityConfig, consumption: i128) -> i128 {
+        let scale = 10_i128.pow(config.consumption_decimals);
+        let mut charged = 0i128;
+        let mut lower = match config.tier_rates.first() {
+            Some(first) => (first.min_units - 1).max(0) * scale,
+            None => 0,
+        };
+        if lower > 0 {
+            charged += Self::charge_for_consumption(config, consumption.min(lower), config.base_rate);
+        }
+        for tier in config.tier_rates.iter() {
+            if consumption <= lower {
+                return charged;
+            }
+            let upper = tier.max_units * scale;
+            let block = consumption.min(upper) - lower;
+            charged += Self::charge_for_consumption(config, block, tier.rate_per_unit);
+            lower = upper;
+        }
+        if consumption > lower {
+            charged += Self::charge_for_consumption(config, consumption - lower, config.base_rate);
+        }
+        charged
+    }
+
+    // Tiers, taken in min_units order, must each span at least two whole units at a
+    // non-negative rate and together form one contiguous range: each tier starts right after the previous one ends.
+    // Returns the tiers sorted by min_units, which is the order they must be stored in.
+    fn validate_tiers(tiers: &Vec<TierRate>) -> Result<Vec<TierRate>, BillingError> {
+        let mut sorted: Vec<TierRate> = Vec::new(tiers.env());
+        for tier in tiers.iter() {
+            if tier.min_units < 0 || tier.max_units <= tier.min_units || tier.rate_per_unit < 0 {
+                return Err(BillingError::InvalidTierRange);
+            }
+            let mut index = sorted.len();
+            while index > 0 && sorted.get(index - 1).unwrap().min_units > tier.min_units {
+                index -= 1;
+            }
+            sorted.insert(index, tier);
+        }
+
+        let mut previous_max: Option<i128> = None;
+        for tier in sorted.iter() {
+            if let Some(previous_max) = previous_max {
+                if tier.min_units <= previous_max {
+                    return Err(BillingError::OverlappingTiers);
+                }
+                if tier.min_units > previous_max + 1 {
+                    return Err(BillingError::TierGap);
+                }
+            }
+            previous_max = Some(tier.max_units);
+        }
+        Ok(sorted)
+    }
+
+    // Hours are inclusive and within a day, days are 0-6 and at least one is given,
+    // and the multiplier must be positive
+    fn validate_tou_rate(rate: &TimeOfUseRate) -> Result<(), BillingError> {
+        if rate.start_hour > rate.end_hour || rate.end_hour > 23 || rate.rate_multiplier <= 0 {
+            return Err(BillingError::InvalidTimeOfUseRate);
+        }
+        if rate.days_of_week.is_empty() || rate.days_of_week.iter().any(|day| day > 6) {
+            return Err(BillingError::InvalidTimeOfUseRate);
+        }
+        Ok(())
+    }
+
+    // Tax percentage within 0..=100 and a non-negative cap, if any
+    fn validate_tax_rate(tax: &TaxRate) -> Result<(), BillingError> {
+        if tax.rate_percentage < 0 || tax.rate_percentage > 100 {
+            return Err(BillingError::InvalidTaxRate);
+        }
+        if let Some(max_amount) = tax.max_amount {
+            if max_amount < 0 {
+                return Err(BillingError::InvalidTaxRate);
+            }
+        }
+        Ok(())
+    }
+
+    // Discount percentage within 0..=100 and an expiry, if any, still in the future
+    fn validate_discount_rate(env: &Env, discount: &DiscountRate) -> Result<(), BillingError> {
+        if discount.discount_percentage < 0 || discount.discount_percentage > 100 {
+            return Err(BillingError::InvalidDiscountRate);
+        }
+        if let Some(expiry_date) = discount.expiry_date {
+            if expiry_date <= env.ledger().timestamp() {
+                return Err(BillingError::InvalidDiscountRate);
+            }
+        }
+        Ok(())
+    }
+
+    // Cap and overage rate can't be negative
+    fn validate_data_cap(data_cap_units: i128, overage_rate: i128) -> Result<(), BillingError> {
+        if data_cap_units < 0 || overage_rate < 0 {
+            return Err(BillingError::InvalidDataCap);
+        }
+        Ok(())
+    }
+
+    // Split reported consumption into the part within an internet config's data cap and
+    // the overage beyond it. Other utilities and uncapped configs have no overage.
+    pub fn split_data_cap(config: &UtilityConfig, consumption: i128) -> (i128, i128) {
+        if config.utility_type != UtilityType::Internet || config.data_cap_units == 0 {
+            return (consumption, 0);
+        }
+        let cap = config.data_cap_units * 10_i128.pow(config.consumption_decimals);
+        if consumption <= cap {
+            (consumption, 0)
+        } else {
+            (cap, consumption - cap)
+        }
+    }
+
+    // Discount on `amount` (the charge for `consumption`) from the config's volume bands.
+    // Each band runs from its threshold to the next one, in whole units.
+    pub fn volume_discount_amount(config: &UtilityConfig, consumption: i128, amount: i128) -> i128 {
+        let units = Self::normalize_consumption(config, consumption);
+        if units <= 0 {
+            return 0;
+        }
+
+        let mut discount = 0i128;
+        let band_count = config.volume_discount.len();
+        for i in 0..band_count {
+            let (threshold, discount_bps) = config.volume_discount.get(i).unwrap();
+            if units <= threshold {
+                break;
+            }
+            let band_end = match config.volume_discount.get(i + 1) {
+                Some((next_threshold, _)) => next_threshold.min(units),
+                None => units,
+            };
+            let band_units = band_end - threshold;
+            discount += (amount * band_units * discount_bps) / (units * 10000);
+        }
+        discount
+    }
+
+    // A feed is consistent with a config when it prices the config's currency
+    // at the same decimal precision the config bills in
+    pub fn check_decimal_consistency(env: Env, config_id: String, feed_id: String) -> bool {
+        let config = match Self::get_utility_config(env.clone(), config_id) {
+            Some(config) => config,
+            None => return false,
+        };
+        let feed = match OracleManager::get_price_feed(env, feed_id) {
+            Some(feed) => feed,
+            None => return false,
+        };
+
+        (feed.base_asset == config.currency || feed.quote_asset == config.currency)
+            && feed.decimals == config.decimals
+    }
+
+    // "P" followed by the hex of the first 8 bytes of sha256(name|region|type|index)
+    fn derive_provider_id(env: &Env, name: &String, region: &String, utility_type: u8, index: u32) -> String {
+        let seed = format!("{}|{}|{}|{}", name, region, utility_type, index);
+        let digest = env.crypto().sha256(&Bytes::from_slice(env, seed.as_bytes())).to_array();
+
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        let mut id = [0u8; 17];
+        id[0] = b'P';
+        for (i, byte) in digest.iter().take(8).enumerate() {
+            id[1 + i * 2] = HEX[(byte >> 4) as usize];
+            id[2 + i * 2] = HEX[(byte & 0x0f) as usize];
+        }
+
+        String::from_bytes(env, &id)
+    }
+
+    // Stable sort of taxes by their `order` field
+    fn sort_taxes_by_order(env: &Env, taxes: Vec<TaxRate>) -> Vec<TaxRate> {
+        let mut sorted: Vec<TaxRate> = Vec::new(env);
+        for tax in taxes.iter() {
+            let mut position = sorted.len();
+            while position > 0 && sorted.get(position - 1).unwrap().order > tax.order {
+                position -= 1;
+            }
+            sorted.insert(position, tax);
+        }
+        sorted
+    }
+
+    // Load a meter on behalf of the provider that operates it
+    fn get_provider_meter(
+        env: &Env,
+        provider_address: &Address,
+        meter_id: &String,
+    ) -> Result<UtilityMeter, BillingError> {
+        let meter = Self::get_meter(env.clone(), meter_id.clone())
+            .ok_or(BillingError::MeterNotFound)?;
+        let provider = Self::get_provider(env.clone(), meter.provider_id.clone())
+            .ok_or(BillingError::ProviderNotFound)?;
+
+        if &provider.address != provider_address {
+            return Err(BillingError::UnauthorizedProvider);
+        }
+
+        Ok(meter)
+    }
+
+    fn store_meter(env: &Env, meter: UtilityMeter) {
+        let mut meters: Map<String, UtilityMeter> = env.storage()
+            .persistent()
+            .get(&UTILITY_METERS)
+            .unwrap_or_else(|| Map::new(env));
+        meters.set(meter.meter_id.clone(), meter);
+        env.storage().persistent().set(&UTILITY_METERS, &meters);
+    }
+
+    // Billing cycle must be 1..=366 days and the grace period can't outlast it
+    fn validate_cycle_periods(billing_cycle_days: u32, grace_period_days: u32) -> Result<(), BillingError> {
+        if billing_cycle_days == 0 || billing_cycle_days > 366 || grace_period_days > billing_cycle_days {
+            return Err(BillingError::InvalidCyclePeriod);
+        }
+        Ok(())
+    }
+
+    // Whether initialize has run
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&UTILITY_INIT)
+    }
+
+    // Reject calls made before initialize
+    fn check_initialized(env: &Env) -> Result<(), BillingError> {
+        if env.storage().instance().has(&UTILITY_INIT) {
+            Ok(())
+        } else {
+            Err(BillingError::NotInitialized)
+        }
+    }
+
     // Validate utility type
-    pub fn validate_utility_type(env: Env, utility_type: u8) -> Result<(), String> {
+    pub fn validate_utility_type(env: Env, utility_type: u8) -> Result<(), BillingError> {
         let utility_types: Map<u8, String> = env.storage()
             .persistent()
             .get(&UTILITY_TYPES)
-            .ok_or("Utility types not initialized")?;
+            .ok_or(BillingError::UtilityTypesNotInitialized)?;
         
         if utility_types.contains_key(utility_type) {
             Ok(())
         } else {
-            Err("Invalid utility type".to_string())
+            Err(BillingError::InvalidUtilityType)
         }
     }
 
@@ -686,4 +2077,20 @@ impl MultiUtilityManager {
             .get(&UTILITY_TYPES)
             .unwrap_or_else(|| Map::new(&env))
     }
+
+    // Code, name, unit and billing style of every supported utility type
+    pub fn get_utility_type_details(env: Env) -> Vec<UtilityTypeDetails> {
+        let mut details = Vec::new(&env);
+        for (code, name) in Self::get_utility_types(env.clone()).iter() {
+            if let Ok(utility_type) = UtilityType::from_u8(code) {
+                details.push_back(UtilityTypeDetails {
+                    code,
+                    name,
+                    unit: utility_type.get_unit(),
+                    is_metered: utility_type.is_metered(),
+                });
+            }
+        }
+        details
+    }
 }
@@ -1,7 +1,8 @@
 #![cfg(test)]
 
+use crate::errors::BillingError;
 use crate::multi_utility::*;
-use soroban_sdk::{Address, Env, String, Symbol};
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env, String, Symbol};
 
 #[test]
 fn test_utility_type_enum() {
@@ -105,7 +106,7 @@ fn test_provider_registration() {
     );
     
     assert!(duplicate_result.is_err());
-    assert_eq!(duplicate_result.unwrap_err(), "Provider already registered");
+    assert_eq!(duplicate_result.unwrap_err(), BillingError::ProviderAlreadyRegistered);
 }
 
 #[test]
@@ -445,6 +446,8 @@ fn test_configuration_upgrade() {
         admin.clone(),
         String::from_str(&"config_001"),
         upgraded_config,
+        false,
+        None,
     );
     
     assert!(result.is_ok());
@@ -455,3 +458,1012 @@ fn test_configuration_upgrade() {
     assert_eq!(upgraded_config_result.base_rate, 1500000i128);
     assert_eq!(upgraded_config_result.billing_cycle_days, 60);
 }
+
+#[test]
+fn test_consumption_scaling_consistent_bills() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let provider_address = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    // Register provider
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Co"),
+        provider_address,
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    // Two configs sharing the same per-kWh rate
+    for config_id in ["config_kwh", "config_wh"] {
+        MultiUtilityManager::add_utility_config(
+            env.clone(),
+            admin.clone(),
+            String::from_str(&config_id),
+            1, // Electricity
+            String::from_str(&"provider_001"),
+            String::from_str(&"Lagos"),
+            1000000i128, // per kWh
+            String::from_str(&"XLM"),
+            7,
+            30,
+            5,
+            1000000i128,
+            100000000i128,
+        ).unwrap();
+    }
+    
+    // kWh meter reports whole units
+    let kwh_config = MultiUtilityManager::get_utility_config(env.clone(), String::from_str(&"config_kwh")).unwrap();
+    assert_eq!(kwh_config.consumption_decimals, 0);
+    
+    // Wh meter reports thousandths of a kWh
+    let mut wh_config = MultiUtilityManager::get_utility_config(env.clone(), String::from_str(&"config_wh")).unwrap();
+    wh_config.consumption_decimals = 3;
+    MultiUtilityManager::upgrade_utility_config(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"config_wh"),
+        wh_config,
+        false,
+        None,
+    ).unwrap();
+    let wh_config = MultiUtilityManager::get_utility_config(env.clone(), String::from_str(&"config_wh")).unwrap();
+    
+    // 12 kWh and 12000 Wh produce the same bill
+    let kwh_bill = MultiUtilityManager::charge_for_consumption(&kwh_config, 12, kwh_config.base_rate);
+    let wh_bill = MultiUtilityManager::charge_for_consumption(&wh_config, 12000, wh_config.base_rate);
+    assert_eq!(kwh_bill, 12000000i128);
+    assert_eq!(kwh_bill, wh_bill);
+    
+    // Fractional kWh are billed proportionally rather than truncated
+    assert_eq!(MultiUtilityManager::charge_for_consumption(&wh_config, 1500, wh_config.base_rate), 1500000i128);
+    assert_eq!(MultiUtilityManager::normalize_consumption(&wh_config, 1500), 1);
+}
+
+#[test]
+fn test_register_provider_before_initialize_rejected() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Skip initialize on purpose
+    let result = MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Co"),
+        Address::generate(&env),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    );
+    
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), BillingError::NotInitialized);
+    
+    // Nothing was written
+    assert!(MultiUtilityManager::get_provider(env.clone(), String::from_str(&"provider_001")).is_none());
+}
+
+#[test]
+fn test_default_tax_rates() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    // Register provider and config with no taxes of its own
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Co"),
+        Address::generate(&env),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    MultiUtilityManager::add_utility_config(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"config_001"),
+        1, // Electricity
+        String::from_str(&"provider_001"),
+        String::from_str(&"Lagos"),
+        1000000i128,
+        String::from_str(&"XLM"),
+        7,
+        30,
+        5,
+        1000000i128,
+        100000000i128,
+    ).unwrap();
+    
+    // Regional VAT default
+    MultiUtilityManager::set_default_tax(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"Lagos"),
+        1,
+        TaxRate {
+            tax_name: String::from_str(&"VAT"),
+            rate_percentage: 7,
+            is_compound: false,
+            max_amount: None,
+            order: 0,
+        },
+    ).unwrap();
+    
+    // Default applies to a config that defines no taxes
+    let config = MultiUtilityManager::get_utility_config(env.clone(), String::from_str(&"config_001")).unwrap();
+    let taxes = MultiUtilityManager::get_effective_tax_rates(env.clone(), config.clone());
+    assert_eq!(taxes.len(), 1);
+    assert_eq!(taxes.get(0).unwrap().rate_percentage, 7);
+    
+    // A config tax with the same name overrides the default
+    let mut overriding_config = config;
+    overriding_config.tax_rates.push_back(TaxRate {
+        tax_name: String::from_str(&"VAT"),
+        rate_percentage: 5,
+        is_compound: false,
+        max_amount: None,
+        order: 0,
+    });
+    let taxes = MultiUtilityManager::get_effective_tax_rates(env.clone(), overriding_config);
+    assert_eq!(taxes.len(), 1);
+    assert_eq!(taxes.get(0).unwrap().rate_percentage, 5);
+}
+
+#[test]
+fn test_structured_error_variants() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let provider_address = Address::generate(&env);
+    
+    // Stable numeric codes
+    assert_eq!(BillingError::NotInitialized as u32, 1);
+    assert_eq!(BillingError::MeterNotFound as u32, 12);
+    assert_eq!(BillingError::RateUnavailable as u32, 16);
+    assert_eq!(BillingError::AmountTooLow as u32, 20);
+    
+    // Enum conversions
+    assert_eq!(UtilityType::from_u8(99).unwrap_err(), BillingError::InvalidUtilityType);
+    assert_eq!(FeeType::from_u8(99).unwrap_err(), BillingError::InvalidFeeType);
+    
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    // Unknown provider
+    let result = MultiUtilityManager::add_utility_config(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"config_001"),
+        1,
+        String::from_str(&"missing_provider"),
+        String::from_str(&"Lagos"),
+        1000000i128,
+        String::from_str(&"XLM"),
+        7,
+        30,
+        5,
+        1000000i128,
+        100000000i128,
+    );
+    assert_eq!(result.unwrap_err(), BillingError::ProviderNotFound);
+    
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Co"),
+        provider_address.clone(),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    // Config type doesn't match provider
+    let result = MultiUtilityManager::add_utility_config(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"config_001"),
+        2, // Water
+        String::from_str(&"provider_001"),
+        String::from_str(&"Lagos"),
+        1000000i128,
+        String::from_str(&"XLM"),
+        7,
+        30,
+        5,
+        1000000i128,
+        100000000i128,
+    );
+    assert_eq!(result.unwrap_err(), BillingError::UtilityTypeMismatch);
+    
+    // Meter registered by the wrong provider address
+    let result = MultiUtilityManager::register_meter(
+        env.clone(),
+        Address::generate(&env),
+        String::from_str(&"meter_001"),
+        1,
+        String::from_str(&"provider_001"),
+        Address::generate(&env),
+        String::from_str(&"123 Main St"),
+        String::from_str(&"SmartMeter X1"),
+        String::from_str(&"v1.0.0"),
+        true,
+    );
+    assert_eq!(result.unwrap_err(), BillingError::UnauthorizedProvider);
+    
+    // Unknown provider status update
+    let result = MultiUtilityManager::update_provider_status(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"missing_provider"),
+        false,
+    );
+    assert_eq!(result.unwrap_err(), BillingError::ProviderNotFound);
+}
+
+#[test]
+fn test_submit_readings_batch() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let provider_address = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Power Co"),
+        provider_address.clone(),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    for meter_id in ["meter_001", "meter_002"] {
+        MultiUtilityManager::register_meter(
+            env.clone(),
+            provider_address.clone(),
+            String::from_str(&meter_id),
+            1,
+            String::from_str(&"provider_001"),
+            Address::generate(&env),
+            String::from_str(&"123 Main St"),
+            String::from_str(&"SmartMeter X1"),
+            String::from_str(&"v1.0.0"),
+            true,
+        ).unwrap();
+    }
+    
+    let now = env.ledger().timestamp();
+    let mut first_batch = soroban_sdk::Vec::new(&env);
+    first_batch.push_back((String::from_str(&"meter_001"), 120i128, now + 3600));
+    first_batch.push_back((String::from_str(&"meter_002"), 80i128, now + 3600));
+    MultiUtilityManager::submit_readings_batch(env.clone(), provider_address.clone(), first_batch).unwrap();
+    
+    // One valid reading and one that goes backwards
+    let mut batch = soroban_sdk::Vec::new(&env);
+    batch.push_back((String::from_str(&"meter_001"), 150i128, now + 7200));
+    batch.push_back((String::from_str(&"meter_002"), 60i128, now + 7200));
+    let results = MultiUtilityManager::submit_readings_batch(env.clone(), provider_address.clone(), batch).unwrap();
+    
+    assert_eq!(results.len(), 2);
+    let valid = results.get(0).unwrap();
+    assert!(valid.accepted);
+    assert_eq!(valid.consumption, 30);
+    
+    let rejected = results.get(1).unwrap();
+    assert!(!rejected.accepted);
+    assert_eq!(rejected.error_code, BillingError::ReadingRollback as u32);
+    
+    // Rejected entry left the meter untouched
+    let meter = MultiUtilityManager::get_meter(env.clone(), String::from_str(&"meter_002")).unwrap();
+    assert_eq!(meter.last_reading, 80);
+    let meter = MultiUtilityManager::get_meter(env.clone(), String::from_str(&"meter_001")).unwrap();
+    assert_eq!(meter.last_reading, 150);
+    
+    // Oversized batches are refused outright
+    let mut oversized = soroban_sdk::Vec::new(&env);
+    for i in 0..101u64 {
+        oversized.push_back((String::from_str(&"meter_001"), 200i128, now + 10000 + i));
+    }
+    let result = MultiUtilityManager::submit_readings_batch(env.clone(), provider_address, oversized);
+    assert_eq!(result.unwrap_err(), BillingError::BatchTooLarge);
+}
+
+#[test]
+fn test_list_provider_fees() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    for provider_id in ["provider_001", "provider_002"] {
+        MultiUtilityManager::register_provider(
+            env.clone(),
+            admin.clone(),
+            String::from_str(&provider_id),
+            String::from_str(&"Test Internet Co"),
+            Address::generate(&env),
+            4, // Internet
+            String::from_str(&"Port Harcourt"),
+            String::from_str(&"LICENSE001"),
+            String::from_str(&"contact@test.com"),
+        ).unwrap();
+    }
+    
+    // Three fees for provider_001, one for provider_002
+    for (fee_id, provider_id, fee_type) in [
+        ("fee_001", "provider_001", 1u8),
+        ("fee_002", "provider_001", 2u8),
+        ("fee_003", "provider_001", 3u8),
+        ("fee_004", "provider_002", 1u8),
+    ] {
+        MultiUtilityManager::add_utility_fee(
+            env.clone(),
+            admin.clone(),
+            String::from_str(&fee_id),
+            4, // Internet
+            String::from_str(&provider_id),
+            fee_type,
+            1000000i128,
+            None,
+            false,
+            String::from_str(&"Test fee"),
+        ).unwrap();
+    }
+    
+    let fees = MultiUtilityManager::list_provider_fees(env.clone(), String::from_str(&"provider_001"), false);
+    assert_eq!(fees.len(), 3);
+    
+    // Deactivated fee is hidden only when active_only is set
+    MultiUtilityManager::update_fee_status(env.clone(), admin.clone(), String::from_str(&"fee_002"), false).unwrap();
+    
+    let all_fees = MultiUtilityManager::list_provider_fees(env.clone(), String::from_str(&"provider_001"), false);
+    assert_eq!(all_fees.len(), 3);
+    
+    let active_fees = MultiUtilityManager::list_provider_fees(env.clone(), String::from_str(&"provider_001"), true);
+    assert_eq!(active_fees.len(), 2);
+    for fee in active_fees.iter() {
+        assert!(fee.fee_id != String::from_str(&"fee_002"));
+    }
+}
+
+#[test]
+fn test_config_version_history() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Co"),
+        Address::generate(&env),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    MultiUtilityManager::add_utility_config(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"config_001"),
+        1, // Electricity
+        String::from_str(&"provider_001"),
+        String::from_str(&"Lagos"),
+        1000000i128,
+        String::from_str(&"XLM"),
+        7,
+        30,
+        5,
+        1000000i128,
+        100000000i128,
+    ).unwrap();
+    
+    // No upgrades yet
+    assert_eq!(MultiUtilityManager::list_config_versions(env.clone(), String::from_str(&"config_001")).len(), 0);
+    
+    // Upgrade twice
+    for new_rate in [1500000i128, 2000000i128] {
+        let mut config = MultiUtilityManager::get_utility_config(env.clone(), String::from_str(&"config_001")).unwrap();
+        config.base_rate = new_rate;
+        MultiUtilityManager::upgrade_utility_config(
+            env.clone(),
+            admin.clone(),
+            String::from_str(&"config_001"),
+            config,
+            false,
+            None,
+        ).unwrap();
+    }
+    
+    let versions = MultiUtilityManager::list_config_versions(env.clone(), String::from_str(&"config_001"));
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions.get(0).unwrap(), 2);
+    assert_eq!(versions.get(1).unwrap(), 3);
+    
+    let version_2 = MultiUtilityManager::get_config_version(env.clone(), String::from_str(&"config_001"), 2).unwrap();
+    assert_eq!(version_2.version, 2);
+    assert_eq!(version_2.utility_type, UtilityType::Electricity);
+    
+    let version_3 = MultiUtilityManager::get_config_version(env.clone(), String::from_str(&"config_001"), 3).unwrap();
+    assert_eq!(version_3.version, 3);
+    
+    assert!(MultiUtilityManager::get_config_version(env.clone(), String::from_str(&"config_001"), 4).is_none());
+}
+
+#[test]
+fn test_cycle_and_grace_period_bounds() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Co"),
+        Address::generate(&env),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    // (billing_cycle_days, grace_period_days): zero cycle, over-long cycle, grace beyond cycle
+    for (cycle, grace) in [(0u32, 0u32), (3650000u32, 5u32), (30u32, 31u32)] {
+        let result = MultiUtilityManager::add_utility_config(
+            env.clone(),
+            admin.clone(),
+            String::from_str(&"config_001"),
+            1, // Electricity
+            String::from_str(&"provider_001"),
+            String::from_str(&"Lagos"),
+            1000000i128,
+            String::from_str(&"XLM"),
+            7,
+            cycle,
+            grace,
+            1000000i128,
+            100000000i128,
+        );
+        assert_eq!(result.unwrap_err(), BillingError::InvalidCyclePeriod);
+    }
+    
+    // Boundary values are accepted
+    let result = MultiUtilityManager::add_utility_config(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"config_001"),
+        1, // Electricity
+        String::from_str(&"provider_001"),
+        String::from_str(&"Lagos"),
+        1000000i128,
+        String::from_str(&"XLM"),
+        7,
+        366,
+        366,
+        1000000i128,
+        100000000i128,
+    );
+    assert!(result.is_ok());
+}
+
+fn register_rating_customer_meter(env: &Env, provider_address: &Address, meter_id: &str, customer: &Address) {
+    MultiUtilityManager::register_meter(
+        env.clone(),
+        provider_address.clone(),
+        String::from_str(&meter_id),
+        1, // Electricity
+        String::from_str(&"provider_001"),
+        customer.clone(),
+        String::from_str(&"123 Main St"),
+        String::from_str(&"SmartMeter X1"),
+        String::from_str(&"v1.0.0"),
+        true,
+    ).unwrap();
+}
+
+#[test]
+fn test_provider_rating_distribution() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let provider_id = String::from_str(&"provider_001");
+    let provider_address = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        provider_id.clone(),
+        String::from_str(&"Test Co"),
+        provider_address.clone(),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    // Mostly 5-star with a couple of 1-stars and one 3-star, each from its own customer
+    let scores = [5u8, 5, 5, 1, 3, 1, 5];
+    let meter_ids = ["meter_001", "meter_002", "meter_003", "meter_004", "meter_005", "meter_006", "meter_007"];
+    for (score, meter_id) in scores.iter().zip(meter_ids.iter()) {
+        let customer = Address::generate(&env);
+        register_rating_customer_meter(&env, &provider_address, meter_id, &customer);
+        MultiUtilityManager::submit_provider_rating(
+            env.clone(),
+            customer,
+            provider_id.clone(),
+            *score,
+        ).unwrap();
+    }
+    
+    let distribution = MultiUtilityManager::get_rating_distribution(env.clone(), provider_id.clone());
+    assert_eq!(distribution, [2, 0, 1, 0, 4]);
+    
+    // Out-of-range scores are rejected and not counted
+    let result = MultiUtilityManager::submit_provider_rating(
+        env.clone(),
+        Address::generate(&env),
+        provider_id.clone(),
+        6,
+    );
+    assert_eq!(result.unwrap_err(), BillingError::InvalidRating);
+    assert_eq!(MultiUtilityManager::get_rating_distribution(env.clone(), provider_id), [2, 0, 1, 0, 4]);
+}
+
+#[test]
+fn test_config_currency_change_requires_migration() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let config_id = String::from_str(&"config_001");
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Co"),
+        Address::generate(&env),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    MultiUtilityManager::add_utility_config(
+        env.clone(),
+        admin.clone(),
+        config_id.clone(),
+        1, // Electricity
+        String::from_str(&"provider_001"),
+        String::from_str(&"Lagos"),
+        1000000i128,
+        String::from_str(&"NGN"),
+        7,
+        30,
+        5,
+        1000000i128,
+        100000000i128,
+    ).unwrap();
+    
+    let mut usd_config = MultiUtilityManager::get_utility_config(env.clone(), config_id.clone()).unwrap();
+    usd_config.currency = String::from_str(&"USD");
+    
+    // A silent change, or one without a migration note, is blocked
+    for (allow, note) in [(false, None), (true, None), (false, Some(String::from_str(&"Switch to USD")))] {
+        let result = MultiUtilityManager::upgrade_utility_config(
+            env.clone(),
+            admin.clone(),
+            config_id.clone(),
+            usd_config.clone(),
+            allow,
+            note,
+        );
+        assert_eq!(result.unwrap_err(), BillingError::CurrencyChangeRequiresMigration);
+    }
+    
+    // Explicitly allowed with a note
+    MultiUtilityManager::upgrade_utility_config(
+        env.clone(),
+        admin.clone(),
+        config_id.clone(),
+        usd_config,
+        true,
+        Some(String::from_str(&"Switch to USD")),
+    ).unwrap();
+    
+    let config = MultiUtilityManager::get_utility_config(env.clone(), config_id.clone()).unwrap();
+    assert_eq!(config.currency, String::from_str(&"USD"));
+    
+    let version = MultiUtilityManager::get_config_version(env.clone(), config_id, config.version).unwrap();
+    assert_eq!(version.description, String::from_str(&"Switch to USD"));
+}
+
+#[test]
+fn test_auto_provider_ids_are_distinct() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    let mut ids = soroban_sdk::Vec::new(&env);
+    for region in ["Lagos", "Abuja"] {
+        let provider_id = MultiUtilityManager::register_provider_auto_id(
+            env.clone(),
+            admin.clone(),
+            String::from_str(&"Sunrise Power"),
+            Address::generate(&env),
+            1, // Electricity
+            String::from_str(&region),
+            String::from_str(&"LICENSE001"),
+            String::from_str(&"contact@test.com"),
+        ).unwrap();
+        
+        let provider = MultiUtilityManager::get_provider(env.clone(), provider_id.clone()).unwrap();
+        assert_eq!(provider.region, String::from_str(&region));
+        ids.push_back(provider_id);
+    }
+    
+    assert_ne!(ids.get(0).unwrap(), ids.get(1).unwrap());
+    
+    // Manual ids keep working alongside generated ones
+    let result = MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_manual"),
+        String::from_str(&"Sunrise Power"),
+        Address::generate(&env),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE002"),
+        String::from_str(&"contact@test.com"),
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_tax_order_independent_of_insertion() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Co"),
+        Address::generate(&env),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    MultiUtilityManager::add_utility_config(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"config_001"),
+        1, // Electricity
+        String::from_str(&"provider_001"),
+        String::from_str(&"Lagos"),
+        1000000i128,
+        String::from_str(&"XLM"),
+        7,
+        30,
+        5,
+        1000000i128,
+        100000000i128,
+    ).unwrap();
+    
+    let tax = |name: &str, order: u32| TaxRate {
+        tax_name: String::from_str(&name),
+        rate_percentage: 5,
+        is_compound: false,
+        max_amount: None,
+        order,
+    };
+    
+    // Levy must apply before VAT, then the stamp duty
+    let config = MultiUtilityManager::get_utility_config(env.clone(), String::from_str(&"config_001")).unwrap();
+    let mut forward = config.clone();
+    forward.tax_rates = soroban_sdk::vec![&env, tax("LEVY", 1), tax("VAT", 2), tax("STAMP", 3)];
+    let mut reversed = config;
+    reversed.tax_rates = soroban_sdk::vec![&env, tax("STAMP", 3), tax("VAT", 2), tax("LEVY", 1)];
+    
+    let forward_taxes = MultiUtilityManager::get_effective_tax_rates(env.clone(), forward);
+    let reversed_taxes = MultiUtilityManager::get_effective_tax_rates(env.clone(), reversed);
+    
+    for (i, name) in ["LEVY", "VAT", "STAMP"].iter().enumerate() {
+        assert_eq!(forward_taxes.get(i as u32).unwrap().tax_name, String::from_str(&name));
+        assert_eq!(reversed_taxes.get(i as u32).unwrap().tax_name, String::from_str(&name));
+    }
+}
+
+#[test]
+fn test_utility_type_details() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    let details = MultiUtilityManager::get_utility_type_details(env.clone());
+    assert_eq!(details.len(), 8);
+    
+    let electricity = details.iter().find(|d| d.code == UtilityType::Electricity.to_u8()).unwrap();
+    assert_eq!(electricity.name, String::from_str(&"electricity"));
+    assert_eq!(electricity.unit, String::from_str(&"kWh"));
+    assert!(electricity.is_metered);
+    
+    let internet = details.iter().find(|d| d.code == UtilityType::Internet.to_u8()).unwrap();
+    assert_eq!(internet.unit, String::from_str(&"Mbps"));
+    assert!(!internet.is_metered);
+}
+
+#[test]
+fn test_get_providers_status() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    for provider_id in ["provider_001", "provider_002"] {
+        MultiUtilityManager::register_provider(
+            env.clone(),
+            admin.clone(),
+            String::from_str(&provider_id),
+            String::from_str(&"Test Power Co"),
+            Address::generate(&env),
+            1, // Electricity
+            String::from_str(&"Lagos"),
+            String::from_str(&"LICENSE001"),
+            String::from_str(&"contact@test.com"),
+        ).unwrap();
+    }
+    
+    MultiUtilityManager::update_provider_status(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_002"),
+        false,
+    ).unwrap();
+    
+    let ids = soroban_sdk::vec![
+        &env,
+        String::from_str(&"provider_001"),
+        String::from_str(&"provider_002"),
+        String::from_str(&"missing_provider"),
+    ];
+    let statuses = MultiUtilityManager::get_providers_status(env.clone(), ids).unwrap();
+    assert_eq!(statuses.len(), 3);
+    assert_eq!(statuses.get(0).unwrap(), (String::from_str(&"provider_001"), Some(true)));
+    assert_eq!(statuses.get(1).unwrap(), (String::from_str(&"provider_002"), Some(false)));
+    assert_eq!(statuses.get(2).unwrap(), (String::from_str(&"missing_provider"), None));
+    
+    // Oversized queries are rejected
+    let mut too_many = soroban_sdk::Vec::new(&env);
+    for _ in 0..101 {
+        too_many.push_back(String::from_str(&"provider_001"));
+    }
+    let result = MultiUtilityManager::get_providers_status(env.clone(), too_many);
+    assert_eq!(result.unwrap_err(), BillingError::BatchTooLarge);
+}
+
+#[test]
+fn test_submit_meter_reading() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let provider_address = Address::generate(&env);
+    
+    // Initialize system
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        String::from_str(&"provider_001"),
+        String::from_str(&"Test Power Co"),
+        provider_address.clone(),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    
+    MultiUtilityManager::register_meter(
+        env.clone(),
+        provider_address.clone(),
+        String::from_str(&"meter_001"),
+        1,
+        String::from_str(&"provider_001"),
+        Address::generate(&env),
+        String::from_str(&"123 Main St"),
+        String::from_str(&"SmartMeter X1"),
+        String::from_str(&"v1.0.0"),
+        true,
+    ).unwrap();
+    
+    let meter_id = String::from_str(&"meter_001");
+    let now = env.ledger().timestamp();
+    
+    // Deltas against the previous cumulative reading
+    let consumption = MultiUtilityManager::submit_meter_reading(env.clone(), provider_address.clone(), meter_id.clone(), 120, now + 3600).unwrap();
+    assert_eq!(consumption, 120);
+    let consumption = MultiUtilityManager::submit_meter_reading(env.clone(), provider_address.clone(), meter_id.clone(), 175, now + 7200).unwrap();
+    assert_eq!(consumption, 55);
+    
+    let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone()).unwrap();
+    assert_eq!(meter.last_reading, 175);
+    assert_eq!(meter.last_reading_date, now + 7200);
+    assert_eq!(MultiUtilityManager::get_total_consumption(env.clone(), meter_id.clone()), 175);
+    
+    // A reading below the last one is a rollback and changes nothing
+    let result = MultiUtilityManager::submit_meter_reading(env.clone(), provider_address.clone(), meter_id.clone(), 150, now + 10800);
+    assert_eq!(result.unwrap_err(), BillingError::ReadingRollback);
+    let meter = MultiUtilityManager::get_meter(env.clone(), meter_id.clone()).unwrap();
+    assert_eq!(meter.last_reading, 175);
+    
+    // Only the meter's provider may report for it
+    let result = MultiUtilityManager::submit_meter_reading(env.clone(), Address::generate(&env), meter_id, 200, now + 10800);
+    assert_eq!(result.unwrap_err(), BillingError::UnauthorizedProvider);
+}
+
+#[test]
+fn test_provider_rating_average_and_cycle_limit() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let provider_id = String::from_str(&"provider_001");
+    let provider_address = Address::generate(&env);
+    let customer = Address::generate(&env);
+    let neighbour = Address::generate(&env);
+
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        provider_id.clone(),
+        String::from_str(&"Test Co"),
+        provider_address.clone(),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+
+    // Only customers with a meter under the provider can rate it
+    let result = MultiUtilityManager::submit_provider_rating(env.clone(), customer.clone(), provider_id.clone(), 4);
+    assert_eq!(result.unwrap_err(), BillingError::NotProviderCustomer);
+
+    register_rating_customer_meter(&env, &provider_address, "meter_001", &customer);
+    register_rating_customer_meter(&env, &provider_address, "meter_002", &neighbour);
+
+    // Out-of-range score
+    let result = MultiUtilityManager::submit_provider_rating(env.clone(), customer.clone(), provider_id.clone(), 0);
+    assert_eq!(result.unwrap_err(), BillingError::InvalidRating);
+
+    MultiUtilityManager::submit_provider_rating(env.clone(), customer.clone(), provider_id.clone(), 4).unwrap();
+    assert_eq!(MultiUtilityManager::get_provider(env.clone(), provider_id.clone()).unwrap().rating, 4);
+
+    MultiUtilityManager::submit_provider_rating(env.clone(), neighbour, provider_id.clone(), 1).unwrap();
+    let provider = MultiUtilityManager::get_provider(env.clone(), provider_id.clone()).unwrap();
+    assert_eq!((provider.rating_count, provider.rating_total, provider.rating), (2, 5, 3));
+
+    // Once per cycle; with no config the cycle is 30 days
+    let result = MultiUtilityManager::submit_provider_rating(env.clone(), customer.clone(), provider_id.clone(), 5);
+    assert_eq!(result.unwrap_err(), BillingError::AlreadyRatedThisCycle);
+
+    env.ledger().with_mut(|li| li.timestamp += 30 * 86400);
+    MultiUtilityManager::submit_provider_rating(env.clone(), customer, provider_id.clone(), 5).unwrap();
+    assert_eq!(MultiUtilityManager::get_provider(env.clone(), provider_id).unwrap().rating, 3);
+}
+
+#[test]
+fn test_deregister_provider() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let provider_id = String::from_str(&"provider_001");
+    let provider_address = Address::generate(&env);
+    let customer = Address::generate(&env);
+
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin.clone(),
+        provider_id.clone(),
+        String::from_str(&"Test Co"),
+        provider_address.clone(),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    register_rating_customer_meter(&env, &provider_address, "meter_001", &customer);
+
+    // A live meter still points at the provider
+    let result = MultiUtilityManager::deregister_provider(env.clone(), admin.clone(), provider_id.clone());
+    assert_eq!(result.unwrap_err(), BillingError::ProviderHasActiveMeters);
+    assert!(MultiUtilityManager::get_provider(env.clone(), provider_id.clone()).is_some());
+
+    // Once everything is deactivated the provider can go
+    let (_, _, complete) = MultiUtilityManager::deactivate_provider_cascade(env.clone(), admin.clone(), provider_id.clone()).unwrap();
+    assert!(complete);
+    MultiUtilityManager::deregister_provider(env.clone(), admin.clone(), provider_id.clone()).unwrap();
+    assert!(MultiUtilityManager::get_provider(env.clone(), provider_id.clone()).is_none());
+
+    let result = MultiUtilityManager::deregister_provider(env.clone(), admin, provider_id);
+    assert_eq!(result.unwrap_err(), BillingError::ProviderNotFound);
+}
+
+#[test]
+fn test_transfer_meter() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let provider_id = String::from_str(&"provider_001");
+    let provider_address = Address::generate(&env);
+    let old_customer = Address::generate(&env);
+    let new_customer = Address::generate(&env);
+
+    MultiUtilityManager::initialize(env.clone(), admin.clone());
+    MultiUtilityManager::register_provider(
+        env.clone(),
+        admin,
+        provider_id,
+        String::from_str(&"Test Co"),
+        provider_address.clone(),
+        1, // Electricity
+        String::from_str(&"Lagos"),
+        String::from_str(&"LICENSE001"),
+        String::from_str(&"contact@test.com"),
+    ).unwrap();
+    register_rating_customer_meter(&env, &provider_address, "meter_001", &old_customer);
+    let meter_id = String::from_str(&"meter_001");
+    assert_eq!(MultiUtilityManager::list_meters_by_customer(env.clone(), old_customer.clone()).len(), 1);
+
+    // Only the owning provider may hand the meter over
+    let result = MultiUtilityManager::transfer_meter(env.clone(), Address::generate(&env), meter_id.clone(), new_customer.clone());
+    assert_eq!(result.unwrap_err(), BillingError::UnauthorizedProvider);
+
+    env.ledger().with_mut(|li| li.timestamp += 86400);
+    let previous = MultiUtilityManager::transfer_meter(env.clone(), provider_address, meter_id.clone(), new_customer.clone()).unwrap();
+    assert_eq!(previous, old_customer);
+
+    assert_eq!(MultiUtilityManager::list_meters_by_customer(env.clone(), old_customer).len(), 0);
+    let moved = MultiUtilityManager::list_meters_by_customer(env.clone(), new_customer.clone());
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved.get(0).unwrap().meter_id, meter_id);
+    assert_eq!(moved.get(0).unwrap().last_reading_date, env.ledger().timestamp());
+}
@@ -8,10 +8,36 @@ use soroban_fixed_point_math::FixedPoint;
 // Storage keys for oracle data
 const ORACLE_PRICE_FEEDS: Symbol = symbol_short!("OP_FEEDS");
 const ORACLE_UTILITY_RATES: Symbol = symbol_short!("UT_RATES");
+const ORACLE_RATE_SOURCES: Symbol = symbol_short!("UT_RSRC");
+const ORACLE_FEED_SOURCES: Symbol = symbol_short!("OP_FSRC");
+const ORACLE_PRICE_HISTORY: Symbol = symbol_short!("OP_HIST");
+const ORACLE_BASE_CURRENCY: Symbol = symbol_short!("OR_BASE");
+const ORACLE_FEED_RELIABILITY: Symbol = symbol_short!("OR_FREL");
+const ORACLE_RATE_RELIABILITY: Symbol = symbol_short!("OR_RREL");
 const ORACLE_CONFIG: Symbol = symbol_short!("OR_CONF");
 const ORACLE_RELIABILITY: Symbol = symbol_short!("OR_REL");
 const ORACLE_COSTS: Symbol = symbol_short!("OR_COST");
 const ORACLE_SCHEDULE: Symbol = symbol_short!("OR_SCH");
+const ORACLE_FEED_STREAKS: Symbol = symbol_short!("OR_STRK");
+const ORACLE_ADMIN: Symbol = symbol_short!("OR_ADMIN");
+
+// Largest decimal precision accepted for feeds and utility configs
+pub const MAX_DECIMALS: u32 = 18;
+
+// Price samples kept per feed for TWAP
+const MAX_PRICE_SAMPLES: u32 = 24;
+
+// Reliability weighting used when a config leaves both weights at zero
+const DEFAULT_SUCCESS_WEIGHT_BPS: u32 = 5000;
+const DEFAULT_LATENCY_WEIGHT_BPS: u32 = 5000;
+
+// Convert `amount` at a `price` quoted with `decimals` places, failing instead of
+// trapping when the intermediate product overflows
+pub fn safe_convert(amount: i128, price: i128, decimals: u32) -> Result<i128, String> {
+    let scale = 10_i128.checked_pow(decimals).ok_or("Conversion overflow")?;
+    let product = amount.checked_mul(price).ok_or("Conversion overflow")?;
+    product.checked_div(scale).ok_or("Conversion overflow".to_string())
+}
 
 // Oracle data structures
 #[derive(Clone)]
@@ -41,6 +67,13 @@ pub struct OracleConfig {
     pub min_reliability_score: u8,
     pub fallback_enabled: bool,
     pub cost_limit_per_call: i128,
+    pub recovery_update_count: u32, // Consecutive timely updates that restore a degraded feed
+    pub min_update_interval_seconds: u64, // Minimum spacing between updates of one feed
+    pub success_weight_bps: u32, // Share of the reliability score from success rate
+    pub latency_weight_bps: u32, // Share of the reliability score from response time
+    pub max_deviation_bps: u32, // Largest move from the stored price one update may make; 0 = unchecked
+    pub period_budget: i128, // Most that may be spent on oracle calls per period; 0 = unlimited
+    pub period_seconds: u64, // Length of a budget period
 }
 
 #[derive(Clone)]
@@ -60,6 +93,8 @@ pub struct OracleCost {
     pub daily_limit: i128,
     pub daily_spent: i128,
     pub last_reset: u64,
+    pub period_spent: i128,
+    pub period_start: u64,
 }
 
 #[derive(Clone)]
@@ -84,6 +119,7 @@ impl OracleManager {
         admin.require_auth();
         
         // Set initial configuration
+        env.storage().instance().set(&ORACLE_ADMIN, &admin);
         env.storage().instance().set(&ORACLE_CONFIG, &config);
         
         // Initialize reliability tracking
@@ -104,6 +140,8 @@ impl OracleManager {
             daily_limit: 1000000, // 0.001 XLM default
             daily_spent: 0,
             last_reset: env.ledger().timestamp(),
+            period_spent: 0,
+            period_start: env.ledger().timestamp(),
         };
         env.storage().instance().set(&ORACLE_COSTS, &cost);
         
@@ -125,6 +163,10 @@ impl OracleManager {
         price_feed: PriceFeed,
     ) {
         admin.require_auth();
+
+        if price_feed.decimals > MAX_DECIMALS {
+            panic!("Invalid decimals");
+        }
         
         let mut feeds: Map<String, PriceFeed> = env.storage()
             .persistent()
@@ -144,44 +186,401 @@ impl OracleManager {
         feeds.get(feed_id)
     }
 
+    // Add an extra source for a price feed. Its data is stored as feed
+    // `{feed_id}_{source_index}` and updated like any other feed.
+    pub fn add_price_feed_source(
+        env: Env,
+        admin: Address,
+        feed_id: String,
+        source_index: u32,
+        price_feed: PriceFeed,
+    ) {
+        let source_id = format!("{}_{}", feed_id, source_index);
+        Self::add_price_feed(env.clone(), admin, source_id.clone(), price_feed);
+
+        let mut sources: Map<String, Vec<String>> = env.storage()
+            .persistent()
+            .get(&ORACLE_FEED_SOURCES)
+            .unwrap_or_else(|| Map::new(&env));
+        let mut ids = sources.get(feed_id.clone()).unwrap_or_else(|| Vec::new(&env));
+        if !ids.contains(&source_id) {
+            ids.push_back(source_id);
+        }
+        sources.set(feed_id, ids);
+        env.storage().persistent().set(&ORACLE_FEED_SOURCES, &sources);
+    }
+
+    // Median price over the feed and its extra sources, skipping any that are stale or
+    // below the minimum reliability score. None when no source qualifies.
+    pub fn get_aggregated_price(env: Env, feed_id: String) -> Option<i128> {
+        let config: OracleConfig = env.storage()
+            .instance()
+            .get(&ORACLE_CONFIG)?;
+        let feeds: Map<String, PriceFeed> = env.storage()
+            .persistent()
+            .get(&ORACLE_PRICE_FEEDS)?;
+
+        let sources: Map<String, Vec<String>> = env.storage()
+            .persistent()
+            .get(&ORACLE_FEED_SOURCES)
+            .unwrap_or_else(|| Map::new(&env));
+        let mut feed_ids = sources.get(feed_id.clone()).unwrap_or_else(|| Vec::new(&env));
+        feed_ids.push_front(feed_id);
+
+        // Keep qualifying prices sorted as they are collected
+        let now = env.ledger().timestamp();
+        let mut prices: Vec<i128> = Vec::new(&env);
+        for id in feed_ids.iter() {
+            if let Some(feed) = feeds.get(id) {
+                if now > feed.last_updated + config.max_age_seconds
+                    || feed.reliability_score < config.min_reliability_score
+                {
+                    continue;
+                }
+                let mut index = 0;
+                while index < prices.len() && prices.get(index).unwrap() < feed.price {
+                    index += 1;
+                }
+                prices.insert(index, feed.price);
+            }
+        }
+
+        let count = prices.len();
+        if count == 0 {
+            return None;
+        }
+        let middle = prices.get(count / 2).unwrap();
+        if count % 2 == 1 {
+            Some(middle)
+        } else {
+            Some((prices.get(count / 2 - 1).unwrap() + middle) / 2)
+        }
+    }
+
+    // Admin: Retire a feed along with its samples, streak, reliability and source links
+    pub fn remove_price_feed(env: Env, admin: Address, feed_id: String) -> Result<(), String> {
+        Self::require_oracle_admin(&env, &admin)?;
+
+        let mut feeds: Map<String, PriceFeed> = env.storage()
+            .persistent()
+            .get(&ORACLE_PRICE_FEEDS)
+            .ok_or("Price feed not found")?;
+        if !feeds.contains_key(feed_id.clone()) {
+            return Err("Feed ID not found".to_string());
+        }
+        feeds.remove(feed_id.clone());
+        env.storage().persistent().set(&ORACLE_PRICE_FEEDS, &feeds);
+
+        if let Some(mut history) = env.storage().persistent().get::<Symbol, Map<String, Vec<(i128, u64)>>>(&ORACLE_PRICE_HISTORY) {
+            history.remove(feed_id.clone());
+            env.storage().persistent().set(&ORACLE_PRICE_HISTORY, &history);
+        }
+        if let Some(mut streaks) = env.storage().persistent().get::<Symbol, Map<String, u32>>(&ORACLE_FEED_STREAKS) {
+            streaks.remove(feed_id.clone());
+            env.storage().persistent().set(&ORACLE_FEED_STREAKS, &streaks);
+        }
+        if let Some(mut per_feed) = env.storage().persistent().get::<Symbol, Map<String, OracleReliability>>(&ORACLE_FEED_RELIABILITY) {
+            per_feed.remove(feed_id.clone());
+            env.storage().persistent().set(&ORACLE_FEED_RELIABILITY, &per_feed);
+        }
+
+        // Drop its own source list and its entry in any other feed's sources
+        if let Some(sources) = env.storage().persistent().get::<Symbol, Map<String, Vec<String>>>(&ORACLE_FEED_SOURCES) {
+            let mut updated: Map<String, Vec<String>> = Map::new(&env);
+            for (base_id, mut ids) in sources.iter() {
+                if base_id == feed_id {
+                    continue;
+                }
+                if let Some(index) = ids.first_index_of(&feed_id) {
+                    ids.remove(index);
+                }
+                updated.set(base_id, ids);
+            }
+            env.storage().persistent().set(&ORACLE_FEED_SOURCES, &updated);
+        }
+
+        Ok(())
+    }
+
+    // Ids of every registered feed, including extra sources
+    pub fn list_price_feeds(env: Env) -> Vec<String> {
+        let feeds: Map<String, PriceFeed> = env.storage()
+            .persistent()
+            .get(&ORACLE_PRICE_FEEDS)
+            .unwrap_or_else(|| Map::new(&env));
+        feeds.keys()
+    }
+
     // Update price feed data (simulated oracle call)
     pub fn update_price_feed(
         env: Env,
         feed_id: String,
         new_price: i128,
         timestamp: u64,
+    ) -> Result<(), String> {
+        Self::apply_price_update(env, feed_id, new_price, timestamp, true)
+    }
+
+    // Admin override of the minimum update interval and deviation limit, e.g. to correct
+    // a bad price
+    pub fn force_update_price_feed(
+        env: Env,
+        admin: Address,
+        feed_id: String,
+        new_price: i128,
+        timestamp: u64,
+    ) -> Result<(), String> {
+        Self::require_oracle_admin(&env, &admin)?;
+
+        Self::apply_price_update(env, feed_id, new_price, timestamp, false)
+    }
+
+    fn apply_price_update(
+        env: Env,
+        feed_id: String,
+        new_price: i128,
+        timestamp: u64,
+        enforce_limits: bool,
     ) -> Result<(), String> {
         let config: OracleConfig = env.storage()
             .instance()
             .get(&ORACLE_CONFIG)
             .ok_or("Oracle not initialized")?;
 
-        // Check if data is too old
-        let current_time = env.ledger().timestamp();
-        if current_time > timestamp && (current_time - timestamp) > config.max_age_seconds {
-            return Err("Data too old".to_string());
-        }
-
         let mut feeds: Map<String, PriceFeed> = env.storage()
             .persistent()
             .get(&ORACLE_PRICE_FEEDS)
             .ok_or("Price feed not found")?;
 
         let mut feed = feeds.get(feed_id.clone()).ok_or("Feed ID not found")?;
+        Self::check_price_update(&env, &config, &feed_id, &feed, new_price, timestamp, enforce_limits)?;
+        
+        // Track consecutive timely updates; a gap longer than max_age breaks the streak.
+        // Forced updates skip the deviation check, so they prove nothing and break it too.
+        let mut streaks: Map<String, u32> = env.storage()
+            .persistent()
+            .get(&ORACLE_FEED_STREAKS)
+            .unwrap_or_else(|| Map::new(&env));
+        let gap = timestamp.saturating_sub(feed.last_updated);
+        let streak = if !enforce_limits {
+            0
+        } else if gap > config.max_age_seconds {
+            1
+        } else {
+            streaks.get(feed_id.clone()).unwrap_or(0) + 1
+        };
+        streaks.set(feed_id.clone(), streak);
+        env.storage().persistent().set(&ORACLE_FEED_STREAKS, &streaks);
+        
+        // Let a degraded feed recover once it has proven itself again, landing just above
+        // the threshold rather than on it
+        if feed.reliability_score < config.min_reliability_score
+            && config.recovery_update_count > 0
+            && streak >= config.recovery_update_count
+        {
+            feed.reliability_score = config.min_reliability_score.saturating_add(1).min(100);
+        }
         
         // Update feed data
         feed.price = new_price;
         feed.last_updated = timestamp;
         
-        feeds.set(feed_id, feed);
+        feeds.set(feed_id.clone(), feed);
         env.storage().persistent().set(&ORACLE_PRICE_FEEDS, &feeds);
+
+        // Keep the most recent samples for TWAP, dropping the oldest
+        let mut history: Map<String, Vec<(i128, u64)>> = env.storage()
+            .persistent()
+            .get(&ORACLE_PRICE_HISTORY)
+            .unwrap_or_else(|| Map::new(&env));
+        let mut samples = history.get(feed_id.clone()).unwrap_or_else(|| Vec::new(&env));
+        if samples.len() >= MAX_PRICE_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back((new_price, timestamp));
+        history.set(feed_id.clone(), samples);
+        env.storage().persistent().set(&ORACLE_PRICE_HISTORY, &history);
         
         // Update reliability tracking
-        Self::update_reliability(env, true, 0);
+        Self::update_reliability(env, feed_id, true, 0);
         
         Ok(())
     }
 
+    // Currency that conversions route through when no direct feed exists
+    pub fn set_base_currency(env: Env, admin: Address, currency: String) -> Result<(), String> {
+        Self::require_oracle_admin(&env, &admin)?;
+
+        env.storage().instance().set(&ORACLE_BASE_CURRENCY, &currency);
+        Ok(())
+    }
+
+    pub fn get_base_currency(env: Env) -> String {
+        env.storage()
+            .instance()
+            .get(&ORACLE_BASE_CURRENCY)
+            .unwrap_or_else(|| String::from_str(&env, "USD"))
+    }
+
+    // (rate, decimals) converting amounts in `from` into `to`: the direct `{from}_{to}`
+    // feed when there is one, otherwise `{from}_{base}` composed with `{to}_{base}` at the
+    // finer of the two legs' precision. Aggregated uses each feed's median source price.
+    pub fn get_conversion_rate(
+        env: Env,
+        from: String,
+        to: String,
+        aggregated: bool,
+    ) -> Result<(i128, u32), String> {
+        if let Some(rate) = Self::feed_rate(&env, format!("{}_{}", from, to), aggregated) {
+            return Ok(rate);
+        }
+
+        let base = Self::get_base_currency(env.clone());
+        let leg = |currency: &String| -> Result<(i128, u32), String> {
+            if currency == &base {
+                return Ok((1, 0));
+            }
+            Self::feed_rate(&env, format!("{}_{}", currency, base), aggregated)
+                .ok_or("Exchange rate not found".to_string())
+        };
+        let (from_rate, from_decimals) = leg(&from)?;
+        let (to_rate, to_decimals) = leg(&to)?;
+        if to_rate <= 0 {
+            return Err("Exchange rate not found".to_string());
+        }
+
+        let decimals = from_decimals.max(to_decimals);
+        let scale = 10_i128
+            .checked_pow(decimals - from_decimals + to_decimals)
+            .ok_or("Conversion overflow")?;
+        let rate = from_rate.checked_mul(scale).ok_or("Conversion overflow")? / to_rate;
+        Ok((rate, decimals))
+    }
+
+    // Convert an amount between currencies at spot prices, routing through the base
+    // currency when needed
+    pub fn convert_amount(env: Env, amount: i128, from: String, to: String) -> Result<i128, String> {
+        if from == to {
+            return Ok(amount);
+        }
+        let (rate, decimals) = Self::get_conversion_rate(env, from, to, false)?;
+        safe_convert(amount, rate, decimals)
+    }
+
+    // A feed's (price, decimals), at its median source price when aggregated
+    fn feed_rate(env: &Env, feed_id: String, aggregated: bool) -> Option<(i128, u32)> {
+        let feed = Self::get_price_feed(env.clone(), feed_id.clone())?;
+        let price = if aggregated {
+            Self::get_aggregated_price(env.clone(), feed_id).unwrap_or(feed.price)
+        } else {
+            feed.price
+        };
+        Some((price, feed.decimals))
+    }
+
+    // Time-weighted average price over the last `window_seconds`. Each sample inside the
+    // window holds until the next one (or now); samples older than the window are left
+    // out. None when no sample falls inside the window.
+    pub fn get_twap(env: Env, feed_id: String, window_seconds: u64) -> Option<i128> {
+        let history: Map<String, Vec<(i128, u64)>> = env.storage()
+            .persistent()
+            .get(&ORACLE_PRICE_HISTORY)?;
+        let samples = history.get(feed_id)?;
+
+        let now = env.ledger().timestamp();
+        let window_start = now.saturating_sub(window_seconds);
+
+        let mut weighted_sum: i128 = 0;
+        let mut total_weight: i128 = 0;
+        let mut latest: Option<i128> = None;
+        for i in 0..samples.len() {
+            let (price, timestamp) = samples.get(i).unwrap();
+            if timestamp < window_start || timestamp > now {
+                continue;
+            }
+            let held_until = match samples.get(i + 1) {
+                Some((_, next)) if next <= now => next,
+                _ => now,
+            };
+            weighted_sum += price * (held_until - timestamp) as i128;
+            total_weight += (held_until - timestamp) as i128;
+            latest = Some(price);
+        }
+
+        // Samples that all landed at `now` carry no weight yet; use the newest
+        if total_weight == 0 {
+            return latest;
+        }
+        Some(weighted_sum / total_weight)
+    }
+
+    // Update many feeds at once. Every entry is checked, in order, before any is applied,
+    // so the batch either lands in full or not at all.
+    pub fn update_price_feeds_batch(env: Env, updates: Vec<(String, i128, u64)>) -> Result<(), String> {
+        let config: OracleConfig = env.storage()
+            .instance()
+            .get(&ORACLE_CONFIG)
+            .ok_or("Oracle not initialized")?;
+        let mut pending: Map<String, PriceFeed> = env.storage()
+            .persistent()
+            .get(&ORACLE_PRICE_FEEDS)
+            .ok_or("Price feed not found")?;
+
+        // Later entries for the same feed are checked against the earlier ones
+        for (feed_id, new_price, timestamp) in updates.iter() {
+            let mut feed = pending.get(feed_id.clone()).ok_or("Feed ID not found")?;
+            Self::check_price_update(&env, &config, &feed_id, &feed, new_price, timestamp, true)?;
+            feed.price = new_price;
+            feed.last_updated = timestamp;
+            pending.set(feed_id, feed);
+        }
+
+        for (feed_id, new_price, timestamp) in updates.iter() {
+            Self::apply_price_update(env.clone(), feed_id, new_price, timestamp, true)?;
+        }
+        Ok(())
+    }
+
+    // Checks a price update must pass against the feed's current state. Stale data and
+    // sudden jumps are the feed's fault and count as failures in its reliability; ordering
+    // and throttling rejections are the caller's and don't.
+    fn check_price_update(
+        env: &Env,
+        config: &OracleConfig,
+        feed_id: &String,
+        feed: &PriceFeed,
+        new_price: i128,
+        timestamp: u64,
+        enforce_limits: bool,
+    ) -> Result<(), String> {
+        // Check if data is too old
+        let current_time = env.ledger().timestamp();
+        if current_time > timestamp && (current_time - timestamp) > config.max_age_seconds {
+            Self::update_reliability(env.clone(), feed_id.clone(), false, 0);
+            return Err("Data too old".to_string());
+        }
+
+        // Out-of-order updates must never regress the feed to older data
+        if timestamp <= feed.last_updated {
+            return Err("Stale update".to_string());
+        }
+        
+        // Throttle noisy feeds
+        if enforce_limits && timestamp - feed.last_updated < config.min_update_interval_seconds {
+            return Err("Update too frequent".to_string());
+        }
+
+        // Refuse sudden jumps away from the stored price
+        if enforce_limits && config.max_deviation_bps > 0 && feed.price != 0 {
+            let deviation_bps = (new_price - feed.price).abs() * 10000 / feed.price.abs();
+            if deviation_bps > config.max_deviation_bps as i128 {
+                Self::update_reliability(env.clone(), feed_id.clone(), false, 0);
+                return Err("Price deviation too large".to_string());
+            }
+        }
+        Ok(())
+    }
+
     // Add utility rate
     pub fn add_utility_rate(
         env: Env,
@@ -209,6 +608,70 @@ impl OracleManager {
         rates.get(rate_id)
     }
 
+    // Add an extra source for the `{utility_type}_{region}` rate. Its data is stored as
+    // rate `{utility_type}_{region}_{source_id}` and updated like any other rate.
+    pub fn add_utility_rate_source(
+        env: Env,
+        admin: Address,
+        utility_type: String,
+        region: String,
+        source_id: String,
+        utility_rate: UtilityRate,
+    ) {
+        let base_id = format!("{}_{}", utility_type, region);
+        let rate_id = format!("{}_{}", base_id, source_id);
+        Self::add_utility_rate(env.clone(), admin, rate_id.clone(), utility_rate);
+
+        let mut sources: Map<String, Vec<String>> = env.storage()
+            .persistent()
+            .get(&ORACLE_RATE_SOURCES)
+            .unwrap_or_else(|| Map::new(&env));
+        let mut ids = sources.get(base_id.clone()).unwrap_or_else(|| Vec::new(&env));
+        if !ids.contains(&rate_id) {
+            ids.push_back(rate_id);
+        }
+        sources.set(base_id, ids);
+        env.storage().persistent().set(&ORACLE_RATE_SOURCES, &sources);
+    }
+
+    // Reliability-weighted average of the fresh rates for a utility type and region,
+    // over the primary `{utility_type}_{region}` rate and its extra sources. None when
+    // every rate is stale or missing.
+    pub fn get_aggregated_utility_rate(env: Env, utility_type: String, region: String) -> Option<i128> {
+        let config: OracleConfig = env.storage()
+            .instance()
+            .get(&ORACLE_CONFIG)?;
+        let rates: Map<String, UtilityRate> = env.storage()
+            .persistent()
+            .get(&ORACLE_UTILITY_RATES)?;
+
+        let base_id = format!("{}_{}", utility_type, region);
+        let sources: Map<String, Vec<String>> = env.storage()
+            .persistent()
+            .get(&ORACLE_RATE_SOURCES)
+            .unwrap_or_else(|| Map::new(&env));
+        let mut rate_ids = sources.get(base_id.clone()).unwrap_or_else(|| Vec::new(&env));
+        rate_ids.push_front(base_id);
+
+        let now = env.ledger().timestamp();
+        let mut weighted_sum: i128 = 0;
+        let mut total_weight: i128 = 0;
+        for rate_id in rate_ids.iter() {
+            if let Some(rate) = rates.get(rate_id) {
+                if now > rate.last_updated + config.max_age_seconds {
+                    continue;
+                }
+                weighted_sum += rate.rate_per_kwh * rate.reliability_score as i128;
+                total_weight += rate.reliability_score as i128;
+            }
+        }
+
+        if total_weight == 0 {
+            return None;
+        }
+        Some(weighted_sum / total_weight)
+    }
+
     // Update utility rate
     pub fn update_utility_rate(
         env: Env,
@@ -224,6 +687,7 @@ impl OracleManager {
         // Check if data is too old
         let current_time = env.ledger().timestamp();
         if current_time > timestamp && (current_time - timestamp) > config.max_age_seconds {
+            Self::update_rate_reliability(&env, rate_id, false, 0);
             return Err("Data too old".to_string());
         }
 
@@ -234,15 +698,20 @@ impl OracleManager {
 
         let mut rate = rates.get(rate_id.clone()).ok_or("Rate ID not found")?;
         
+        // Out-of-order updates must never regress the rate to older data
+        if timestamp <= rate.last_updated {
+            return Err("Stale update".to_string());
+        }
+        
         // Update rate data
         rate.rate_per_kwh = new_rate;
         rate.last_updated = timestamp;
         
-        rates.set(rate_id, rate);
+        rates.set(rate_id.clone(), rate);
         env.storage().persistent().set(&ORACLE_UTILITY_RATES, &rates);
         
         // Update reliability tracking
-        Self::update_reliability(env, true, 0);
+        Self::update_rate_reliability(&env, rate_id, true, 0);
         
         Ok(())
     }
@@ -299,19 +768,107 @@ impl OracleManager {
         }
     }
 
-    // Update reliability tracking
-    fn update_reliability(env: Env, success: bool, response_time: u64) {
-        let mut reliability: OracleReliability = env.storage()
+    // Require auth from the oracle admin
+    fn require_oracle_admin(env: &Env, admin: &Address) -> Result<(), String> {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage()
+            .instance()
+            .get(&ORACLE_ADMIN)
+            .ok_or("Oracle not initialized")?;
+        if &stored_admin != admin {
+            return Err("Unauthorized".to_string());
+        }
+        Ok(())
+    }
+
+    // Record the outcome of a call to a feed, in its own tracking and the global totals
+    pub fn update_reliability(env: Env, feed_id: String, success: bool, response_ms: u64) {
+        Self::record_reliability(&env, &ORACLE_FEED_RELIABILITY, feed_id, success, response_ms);
+    }
+
+    // Same as update_reliability for a utility rate. Rates are tracked apart from feeds so
+    // neither skews the other's score, though both count toward the global totals.
+    fn update_rate_reliability(env: &Env, rate_id: String, success: bool, response_ms: u64) {
+        Self::record_reliability(env, &ORACLE_RATE_RELIABILITY, rate_id, success, response_ms);
+    }
+
+    fn record_reliability(env: &Env, key: &Symbol, id: String, success: bool, response_ms: u64) {
+        let mut tracked: Map<String, OracleReliability> = env.storage()
+            .persistent()
+            .get(key)
+            .unwrap_or_else(|| Map::new(env));
+        let mut reliability = tracked.get(id.clone()).unwrap_or_else(Self::empty_reliability);
+        Self::record_outcome(env, &mut reliability, success, response_ms);
+        tracked.set(id, reliability);
+        env.storage().persistent().set(key, &tracked);
+
+        let mut global: OracleReliability = env.storage()
             .instance()
             .get(&ORACLE_RELIABILITY)
-            .unwrap_or_else(|| OracleReliability {
-                success_count: 0,
-                failure_count: 0,
-                last_success: 0,
-                last_failure: 0,
-                average_response_time: 0,
-            });
+            .unwrap_or_else(Self::empty_reliability);
+        Self::record_outcome(env, &mut global, success, response_ms);
+        env.storage().instance().set(&ORACLE_RELIABILITY, &global);
+    }
+
+    // Reliability score (0-100) of one feed from its tracked calls. A feed with no calls
+    // tracked yet keeps its configured score; an unknown feed is neutral.
+    pub fn get_reliability_score(env: Env, feed_id: String) -> u8 {
+        let per_feed: Map<String, OracleReliability> = env.storage()
+            .persistent()
+            .get(&ORACLE_FEED_RELIABILITY)
+            .unwrap_or_else(|| Map::new(&env));
+        if let Some(reliability) = per_feed.get(feed_id.clone()) {
+            return Self::score_of(&env, &reliability);
+        }
+        Self::get_price_feed(env, feed_id)
+            .map(|feed| feed.reliability_score)
+            .unwrap_or(50) // Neutral score
+    }
+
+    // Reliability score (0-100) of one utility rate, tracked apart from price feeds. A
+    // rate with no updates tracked yet keeps its configured score; an unknown rate is neutral.
+    pub fn get_rate_reliability_score(env: Env, rate_id: String) -> u8 {
+        let per_rate: Map<String, OracleReliability> = env.storage()
+            .persistent()
+            .get(&ORACLE_RATE_RELIABILITY)
+            .unwrap_or_else(|| Map::new(&env));
+        if let Some(reliability) = per_rate.get(rate_id.clone()) {
+            return Self::score_of(&env, &reliability);
+        }
+        Self::get_utility_rate(env, rate_id)
+            .map(|rate| rate.reliability_score)
+            .unwrap_or(50) // Neutral score
+    }
+
+    // Average reliability score across every tracked feed
+    pub fn get_global_reliability_score(env: Env) -> u8 {
+        let per_feed: Map<String, OracleReliability> = env.storage()
+            .persistent()
+            .get(&ORACLE_FEED_RELIABILITY)
+            .unwrap_or_else(|| Map::new(&env));
+        if per_feed.len() == 0 {
+            return 50; // Neutral score
+        }
+
+        let mut total: u32 = 0;
+        for (_, reliability) in per_feed.iter() {
+            total += Self::score_of(&env, &reliability) as u32;
+        }
+        (total / per_feed.len()) as u8
+    }
+
+    fn empty_reliability() -> OracleReliability {
+        OracleReliability {
+            success_count: 0,
+            failure_count: 0,
+            last_success: 0,
+            last_failure: 0,
+            average_response_time: 0,
+        }
+    }
 
+    fn record_outcome(env: &Env, reliability: &mut OracleReliability, success: bool, response_time: u64) {
         if success {
             reliability.success_count += 1;
             reliability.last_success = env.ledger().timestamp();
@@ -324,27 +881,13 @@ impl OracleManager {
         let total_calls = reliability.success_count + reliability.failure_count;
         if total_calls > 1 {
             reliability.average_response_time = 
-                (reliability.average_response_time * (total_calls - 1) + response_time) / total_calls;
+                (reliability.average_response_time * (total_calls - 1) as u64 + response_time) / total_calls as u64;
         } else {
             reliability.average_response_time = response_time;
         }
-
-        env.storage().instance().set(&ORACLE_RELIABILITY, &reliability);
     }
 
-    // Get reliability score
-    pub fn get_reliability_score(env: Env) -> u8 {
-        let reliability: OracleReliability = env.storage()
-            .instance()
-            .get(&ORACLE_RELIABILITY)
-            .unwrap_or_else(|| OracleReliability {
-                success_count: 0,
-                failure_count: 0,
-                last_success: 0,
-                last_failure: 0,
-                average_response_time: 0,
-            });
-
+    fn score_of(env: &Env, reliability: &OracleReliability) -> u8 {
         let total_calls = reliability.success_count + reliability.failure_count;
         if total_calls == 0 {
             return 50; // Neutral score
@@ -363,11 +906,25 @@ impl OracleManager {
             25
         };
 
-        // Calculate final score (0-100)
-        let final_score = (success_rate + response_factor) / 2;
+        // Calculate final score (0-100) from the configured weights
+        let (success_weight, latency_weight) = Self::get_reliability_weights(env.clone());
+        let final_score = (success_rate as u64 * success_weight as u64
+            + response_factor as u64 * latency_weight as u64)
+            / (success_weight as u64 + latency_weight as u64);
         (final_score as u8).min(100)
     }
 
+    // (success, latency) weights in basis points, defaulting to an even split
+    fn get_reliability_weights(env: Env) -> (u32, u32) {
+        let config: Option<OracleConfig> = env.storage().instance().get(&ORACLE_CONFIG);
+        match config {
+            Some(c) if c.success_weight_bps + c.latency_weight_bps > 0 => {
+                (c.success_weight_bps, c.latency_weight_bps)
+            }
+            _ => (DEFAULT_SUCCESS_WEIGHT_BPS, DEFAULT_LATENCY_WEIGHT_BPS),
+        }
+    }
+
     // Track oracle costs
     pub fn track_oracle_cost(env: Env, cost: i128) -> Result<(), String> {
         let mut cost_tracker: OracleCost = env.storage()
@@ -398,9 +955,20 @@ impl OracleManager {
             return Err("Daily cost limit exceeded".to_string());
         }
 
+        // Roll the budget period forward by whole periods, then check its budget
+        if config.period_seconds > 0 && current_time >= cost_tracker.period_start + config.period_seconds {
+            let elapsed = current_time - cost_tracker.period_start;
+            cost_tracker.period_start = current_time - elapsed % config.period_seconds;
+            cost_tracker.period_spent = 0;
+        }
+        if config.period_budget > 0 && cost_tracker.period_spent + cost > config.period_budget {
+            return Err("Period budget exceeded".to_string());
+        }
+
         // Update cost tracking
         cost_tracker.total_spent += cost;
         cost_tracker.daily_spent += cost;
+        cost_tracker.period_spent += cost;
         cost_tracker.calls_made += 1;
         
         if cost_tracker.calls_made > 0 {
@@ -474,6 +1042,11 @@ impl OracleManager {
         env.storage().instance().set(&ORACLE_SCHEDULE, &schedule);
     }
 
+    // Whether initialize_oracle has run
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage().instance().has(&ORACLE_CONFIG)
+    }
+
     // Get oracle statistics
     pub fn get_oracle_stats(env: Env) -> (OracleCost, OracleReliability, u8) {
         let cost: OracleCost = env.storage()
@@ -486,6 +1059,8 @@ impl OracleManager {
                 daily_limit: 1000000,
                 daily_spent: 0,
                 last_reset: env.ledger().timestamp(),
+                period_spent: 0,
+                period_start: env.ledger().timestamp(),
             });
 
         let reliability: OracleReliability = env.storage()
@@ -499,7 +1074,7 @@ impl OracleManager {
                 average_response_time: 0,
             });
 
-        let score = Self::get_reliability_score(env);
+        let score = Self::get_global_reliability_score(env);
 
         (cost, reliability, score)
     }
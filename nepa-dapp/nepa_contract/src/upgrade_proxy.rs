@@ -1,12 +1,24 @@
-use soroban_sdk::{contract, contractimpl, Address, Env, Symbol};
+use soroban_sdk::{contract, contractimpl, Address, Env, Symbol, Vec};
 
 #[contract]
 pub struct UpgradeProxy;
 
 #[contractimpl]
 impl UpgradeProxy {
-    /// Initialize the proxy with admin address
-    pub fn initialize(env: Env, admin: Address) {
+    /// Initialize the proxy with admin address. Only valid on a fresh proxy.
+    pub fn initialize(env: Env, admin: Address) -> Result<(), Symbol> {
+        // Re-running this on a live proxy would replace the admin and reset the version
+        if Self::is_initialized(env.clone()) {
+            return Err(Symbol::short("ALR_INIT"));
+        }
+        admin.require_auth();
+
+        Self::write_initial_state(&env, admin);
+        Ok(())
+    }
+
+    // Admin, version 1 and a not-yet-set implementation
+    fn write_initial_state(env: &Env, admin: Address) {
         // Store admin address
         env.storage()
             .instance()
@@ -23,6 +35,48 @@ impl UpgradeProxy {
             .set(&Symbol::short("IMPL"), &Address::from_contract_id(&[0u8; 32]));
     }
 
+    /// Initialize the proxy with a set of upgrade approvers, `required_approvals` of whom
+    /// must approve a proposal before it can be executed. Only valid on a fresh proxy.
+    pub fn initialize_with_approvers(
+        env: Env,
+        admin: Address,
+        approvers: Vec<Address>,
+        required_approvals: u32,
+    ) -> Result<(), Symbol> {
+        // Re-running this on a live proxy would reset the version and approver set
+        if Self::is_initialized(env.clone()) {
+            return Err(Symbol::short("ALR_INIT"));
+        }
+        admin.require_auth();
+
+        // Each approver counts once
+        let mut unique = Vec::new(&env);
+        for approver in approvers.iter() {
+            if !unique.contains(&approver) {
+                unique.push_back(approver);
+            }
+        }
+        if required_approvals == 0 || required_approvals > unique.len() {
+            return Err(Symbol::short("BAD_THRES"));
+        }
+
+        Self::write_initial_state(&env, admin);
+        env.storage()
+            .instance()
+            .set(&Symbol::short("APPROVERS"), &unique);
+        env.storage()
+            .instance()
+            .set(&Symbol::short("THRESHOLD"), &required_approvals);
+        Ok(())
+    }
+
+    /// Whether the proxy has been initialized
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .has(&Symbol::short("ADMIN"))
+    }
+
     /// Get current admin
     pub fn get_admin(env: Env) -> Address {
         env.storage()
@@ -49,17 +103,280 @@ impl UpgradeProxy {
 
     /// Upgrade to new implementation (admin only)
     pub fn upgrade(env: Env, admin: Address, new_implementation: Address, new_version: u32) -> Result<(), Symbol> {
+        admin.require_auth();
+
+        // Verify caller is admin
+        let current_admin = Self::get_admin(env.clone());
+        if current_admin != admin {
+            return Err(Symbol::short("UNAUTHORIZED"));
+        }
+
+        Self::check_direct_switch_allowed(&env)?;
+
+        // Versions only move forward here; going back must use rollback
+        if new_version <= Self::get_version(env.clone()) {
+            return Err(Symbol::short("VER_LOWER"));
+        }
+
+        Self::switch_implementation(&env, new_implementation, new_version, Symbol::short("UPGRADE"));
+        Ok(())
+    }
+
+    /// Set the delay between proposing and executing an upgrade (admin only)
+    pub fn set_timelock(env: Env, admin: Address, timelock_seconds: u64) -> Result<(), Symbol> {
+        admin.require_auth();
+
+        // Verify caller is admin
+        let current_admin = Self::get_admin(env.clone());
+        if current_admin != admin {
+            return Err(Symbol::short("UNAUTHORIZED"));
+        }
+
+        env.storage()
+            .instance()
+            .set(&Symbol::short("TIMELOCK"), &timelock_seconds);
+        Ok(())
+    }
+
+    /// Get the upgrade timelock in seconds (0 = upgrades apply immediately)
+    pub fn get_timelock(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&Symbol::short("TIMELOCK"))
+            .unwrap_or(0)
+    }
+
+    /// Propose an upgrade that can be executed once the timelock has elapsed (admin only).
+    /// A new proposal replaces any pending one and restarts the clock.
+    pub fn propose_upgrade(env: Env, admin: Address, new_implementation: Address, new_version: u32) -> Result<(), Symbol> {
+        admin.require_auth();
+
+        // Verify caller is admin
+        let current_admin = Self::get_admin(env.clone());
+        if current_admin != admin {
+            return Err(Symbol::short("UNAUTHORIZED"));
+        }
+
+        if new_version <= Self::get_version(env.clone()) {
+            return Err(Symbol::short("VER_LOWER"));
+        }
+
+        let proposed_at = env.ledger().timestamp();
+        env.storage()
+            .instance()
+            .set(&Symbol::short("PENDING"), &(new_implementation.clone(), new_version, proposed_at));
+        Self::clear_approvals(&env);
+
+        env.events()
+            .publish(
+                (Symbol::short("UPG_PROP"), new_implementation),
+                (new_version, proposed_at + Self::get_timelock(env.clone())),
+            );
+        Ok(())
+    }
+
+    /// Pending upgrade as (implementation, version, proposed at), if any
+    pub fn get_pending_upgrade(env: Env) -> Option<(Address, u32, u64)> {
+        env.storage()
+            .instance()
+            .get(&Symbol::short("PENDING"))
+    }
+
+    /// Approver signs off on the pending upgrade; approving twice counts once
+    pub fn approve_upgrade(env: Env, approver: Address) -> Result<(), Symbol> {
+        approver.require_auth();
+
+        if !Self::get_approvers(env.clone()).contains(&approver) {
+            return Err(Symbol::short("NOT_APPR"));
+        }
+        let (new_implementation, new_version, _) = Self::get_pending_upgrade(env.clone())
+            .ok_or(Symbol::short("NO_PENDNG"))?;
+
+        let mut approvals = Self::get_upgrade_approvals(env.clone());
+        if approvals.contains(&approver) {
+            return Ok(());
+        }
+        approvals.push_back(approver.clone());
+        env.storage()
+            .instance()
+            .set(&Symbol::short("APPROVALS"), &approvals);
+
+        env.events()
+            .publish(
+                (Symbol::short("UPG_APPR"), new_implementation, approver),
+                (new_version, approvals.len()),
+            );
+        Ok(())
+    }
+
+    /// Addresses allowed to approve upgrades
+    pub fn get_approvers(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::short("APPROVERS"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Approvals needed before a pending upgrade can be executed (0 = none)
+    pub fn get_required_approvals(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&Symbol::short("THRESHOLD"))
+            .unwrap_or(0)
+    }
+
+    /// Approvals recorded so far for the pending upgrade
+    pub fn get_upgrade_approvals(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&Symbol::short("APPROVALS"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    fn clear_approvals(env: &Env) {
+        env.storage()
+            .instance()
+            .remove(&Symbol::short("APPROVALS"));
+    }
+
+    /// Apply the pending upgrade once the timelock has elapsed and enough approvers
+    /// have signed off (admin only)
+    pub fn execute_upgrade(env: Env, admin: Address) -> Result<(), Symbol> {
+        admin.require_auth();
+
+        // Verify caller is admin
+        let current_admin = Self::get_admin(env.clone());
+        if current_admin != admin {
+            return Err(Symbol::short("UNAUTHORIZED"));
+        }
+
+        let (new_implementation, new_version, proposed_at) = Self::get_pending_upgrade(env.clone())
+            .ok_or(Symbol::short("NO_PENDNG"))?;
+
+        if env.ledger().timestamp() < proposed_at + Self::get_timelock(env.clone()) {
+            return Err(Symbol::short("TOO_EARLY"));
+        }
+
+        if Self::get_upgrade_approvals(env.clone()).len() < Self::get_required_approvals(env.clone()) {
+            return Err(Symbol::short("FEW_APPRS"));
+        }
+
+        // Another upgrade may have landed since the proposal
+        if new_version <= Self::get_version(env.clone()) {
+            return Err(Symbol::short("VER_LOWER"));
+        }
+
+        env.storage()
+            .instance()
+            .remove(&Symbol::short("PENDING"));
+        Self::clear_approvals(&env);
+        Self::switch_implementation(&env, new_implementation, new_version, Symbol::short("UPGRADE"));
+        Ok(())
+    }
+
+    /// Drop the pending upgrade (admin only)
+    pub fn cancel_upgrade(env: Env, admin: Address) -> Result<(), Symbol> {
+        admin.require_auth();
+
+        // Verify caller is admin
+        let current_admin = Self::get_admin(env.clone());
+        if current_admin != admin {
+            return Err(Symbol::short("UNAUTHORIZED"));
+        }
+
+        let (new_implementation, new_version, _) = Self::get_pending_upgrade(env.clone())
+            .ok_or(Symbol::short("NO_PENDNG"))?;
+
+        env.storage()
+            .instance()
+            .remove(&Symbol::short("PENDING"));
+        Self::clear_approvals(&env);
+
+        env.events()
+            .publish(
+                (Symbol::short("UPG_CNCL"), new_implementation),
+                new_version,
+            );
+        Ok(())
+    }
+
+    /// Undo the last upgrade, restoring the previous implementation and version (admin only)
+    pub fn rollback(env: Env, admin: Address) -> Result<(), Symbol> {
+        admin.require_auth();
+
         // Verify caller is admin
         let current_admin = Self::get_admin(env.clone());
         if current_admin != admin {
             return Err(Symbol::short("UNAUTHORIZED"));
         }
 
-        // Store old implementation for migration
+        let none = Address::from_contract_id(&[0u8; 32]);
+        let previous_implementation = env.storage()
+            .instance()
+            .get::<Symbol, Address>(&Symbol::short("OLD_IMPL"))
+            .unwrap_or(none.clone());
+        if previous_implementation == none {
+            return Err(Symbol::short("NO_PREV"));
+        }
+
+        let previous_version: u32 = env.storage()
+            .instance()
+            .get(&Symbol::short("OLD_VER"))
+            .unwrap_or(1);
+        if previous_version >= Self::get_version(env.clone()) {
+            return Err(Symbol::short("NOT_RBACK"));
+        }
+
+        Self::switch_implementation(&env, previous_implementation, previous_version, Symbol::short("ROLLBACK"));
+
+        // Only one step back is kept, so the rolled-back implementation can't be restored this way
+        env.storage()
+            .instance()
+            .set(&Symbol::short("OLD_IMPL"), &none);
+        Ok(())
+    }
+
+    /// Intentionally return to a specific earlier version (admin only)
+    pub fn rollback_to(env: Env, admin: Address, previous_implementation: Address, target_version: u32) -> Result<(), Symbol> {
+        admin.require_auth();
+
+        // Verify caller is admin
+        let current_admin = Self::get_admin(env.clone());
+        if current_admin != admin {
+            return Err(Symbol::short("UNAUTHORIZED"));
+        }
+
+        Self::check_direct_switch_allowed(&env)?;
+
+        if target_version >= Self::get_version(env.clone()) {
+            return Err(Symbol::short("NOT_RBACK"));
+        }
+
+        Self::switch_implementation(&env, previous_implementation, target_version, Symbol::short("ROLLBACK"));
+        Ok(())
+    }
+
+    // Once a timelock or approval threshold is configured, installing an arbitrary
+    // implementation has to go through propose/execute
+    fn check_direct_switch_allowed(env: &Env) -> Result<(), Symbol> {
+        if Self::get_timelock(env.clone()) > 0 {
+            return Err(Symbol::short("TL_ACTIVE"));
+        }
+        if Self::get_required_approvals(env.clone()) > 0 {
+            return Err(Symbol::short("NEED_APPR"));
+        }
+        Ok(())
+    }
+
+    fn switch_implementation(env: &Env, new_implementation: Address, new_version: u32, event: Symbol) {
+        // Store old implementation and version for migration and rollback
         let old_implementation = Self::get_implementation(env.clone());
         env.storage()
             .instance()
             .set(&Symbol::short("OLD_IMPL"), &old_implementation);
+        env.storage()
+            .instance()
+            .set(&Symbol::short("OLD_VER"), &Self::get_version(env.clone()));
 
         // Update implementation
         env.storage()
@@ -74,11 +391,9 @@ impl UpgradeProxy {
         // Emit upgrade event
         env.events()
             .publish(
-                (Symbol::short("UPGRADE"), old_implementation, new_implementation),
+                (event, old_implementation, new_implementation),
                 (new_version, env.ledger().timestamp()),
             );
-
-        Ok(())
     }
 
     /// Migrate data from old implementation (admin only)
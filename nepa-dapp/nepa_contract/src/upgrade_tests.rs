@@ -1,8 +1,9 @@
-use soroban_sdk::{Address, Env, Symbol};
+use soroban_sdk::{testutils::Ledger, Address, Env, Symbol};
 use crate::{
     upgrade_proxy::UpgradeProxy,
     version_manager::{VersionManager, ContractVersion},
     data_migration::DataMigration,
+    NepaBillingContract,
     testutils::{Address as _,},
 };
 
@@ -11,7 +12,9 @@ mod tests {
     use super::*;
 
     fn create_test_env() -> Env {
-        Env::default()
+        let env = Env::default();
+        env.mock_all_auths();
+        env
     }
 
     fn create_test_admin(env: &Env) -> Address {
@@ -23,19 +26,35 @@ mod tests {
         let env = create_test_env();
         let admin = create_test_admin(&env);
 
-        UpgradeProxy::initialize(env.clone(), admin.clone());
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
 
         assert_eq!(UpgradeProxy::get_admin(env.clone()), admin);
         assert_eq!(UpgradeProxy::get_version(env.clone()), 1);
     }
 
+    #[test]
+    fn test_upgrade_proxy_rejects_reinitialization() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+        let intruder = Address::generate(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), Address::generate(&env), 2).unwrap();
+
+        // A second initialize can't take over the admin or reset the version
+        let result = UpgradeProxy::initialize(env.clone(), intruder);
+        assert_eq!(result.unwrap_err(), Symbol::short("ALR_INIT"));
+        assert_eq!(UpgradeProxy::get_admin(env.clone()), admin);
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 2);
+    }
+
     #[test]
     fn test_upgrade_proxy_upgrade() {
         let env = create_test_env();
         let admin = create_test_admin(&env);
         let new_implementation = Address::generate(&env);
 
-        UpgradeProxy::initialize(env.clone(), admin.clone());
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
 
         let result = UpgradeProxy::upgrade(
             env.clone(),
@@ -56,7 +75,7 @@ mod tests {
         let unauthorized = Address::generate(&env);
         let new_implementation = Address::generate(&env);
 
-        UpgradeProxy::initialize(env.clone(), admin);
+        UpgradeProxy::initialize(env.clone(), admin).unwrap();
 
         let result = UpgradeProxy::upgrade(
             env.clone(),
@@ -275,12 +294,11 @@ mod tests {
     fn test_integration_upgrade_flow() {
         let env = create_test_env();
         let admin = create_test_admin(&env);
-        let old_implementation = Address::generate(&env);
         let new_implementation = Address::generate(&env);
         let script_hash = [1u8; 32];
 
         // Initialize all systems
-        UpgradeProxy::initialize(env.clone(), admin.clone());
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
         VersionManager::initialize(env.clone(), admin.clone());
         DataMigration::initialize(env.clone(), admin.clone());
 
@@ -304,14 +322,6 @@ mod tests {
             Symbol::short("INTEGRATION_TEST"),
         ).unwrap();
 
-        // Set initial implementation
-        UpgradeProxy::upgrade(
-            env.clone(),
-            admin.clone(),
-            old_implementation.clone(),
-            1,
-        ).unwrap();
-
         // Backup data
         DataMigration::backup_data(env.clone(), admin.clone()).unwrap();
 
@@ -345,7 +355,7 @@ mod tests {
         let unauthorized = Address::generate(&env);
 
         // Test unauthorized access to all systems
-        UpgradeProxy::initialize(env.clone(), admin.clone());
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
         VersionManager::initialize(env.clone(), admin.clone());
         DataMigration::initialize(env.clone(), admin.clone());
 
@@ -377,4 +387,437 @@ mod tests {
         );
         assert!(migration_result.is_err());
     }
+
+    // Upgrade systems at version 1 with version 1 registered as a known, compatible version
+    fn setup_upgrade_systems(env: &Env, admin: &Address) {
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+        VersionManager::initialize(env.clone(), admin.clone());
+        DataMigration::initialize(env.clone(), admin.clone());
+
+        VersionManager::register_version(
+            env.clone(),
+            admin.clone(),
+            1,
+            Address::generate(env),
+            false,
+            true,
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_can_upgrade_to_clean_path() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        setup_upgrade_systems(&env, &admin);
+
+        VersionManager::register_version(
+            env.clone(),
+            admin.clone(),
+            2,
+            Address::generate(&env),
+            true,
+            true,
+        ).unwrap();
+
+        DataMigration::register_migration_script(
+            env.clone(),
+            admin.clone(),
+            1,
+            2,
+            [1u8; 32],
+            Symbol::short("V1_TO_V2"),
+        ).unwrap();
+
+        assert_eq!(NepaBillingContract::can_upgrade_to(env.clone(), 2), Ok(()));
+    }
+
+    #[test]
+    fn test_can_upgrade_to_across_migration_chain() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        setup_upgrade_systems(&env, &admin);
+
+        VersionManager::register_version(env.clone(), admin.clone(), 3, Address::generate(&env), true, true).unwrap();
+
+        // No direct 1 -> 3 script, and no chain yet
+        DataMigration::register_migration_script(env.clone(), admin.clone(), 1, 2, [1u8; 32], Symbol::short("V1_TO_V2")).unwrap();
+        assert_eq!(
+            NepaBillingContract::can_upgrade_to(env.clone(), 3),
+            Err(Symbol::short("MIGRATION_NOT_FOUND"))
+        );
+
+        // 1 -> 2 -> 3 completes the chain
+        DataMigration::register_migration_script(env.clone(), admin.clone(), 2, 3, [2u8; 32], Symbol::short("V2_TO_V3")).unwrap();
+        assert_eq!(NepaBillingContract::can_upgrade_to(env.clone(), 3), Ok(()));
+    }
+
+    #[test]
+    fn test_can_upgrade_to_blocking_conditions() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        setup_upgrade_systems(&env, &admin);
+
+        // Target not registered
+        assert_eq!(
+            NepaBillingContract::can_upgrade_to(env.clone(), 2),
+            Err(Symbol::short("TO_VERSION_NOT_FOUND"))
+        );
+
+        // Target deprecated
+        VersionManager::register_version(env.clone(), admin.clone(), 2, Address::generate(&env), false, true).unwrap();
+        VersionManager::deprecate_version(env.clone(), admin.clone(), 2).unwrap();
+        assert_eq!(
+            NepaBillingContract::can_upgrade_to(env.clone(), 2),
+            Err(Symbol::short("VER_DEPR"))
+        );
+
+        // Not backward compatible
+        VersionManager::register_version(env.clone(), admin.clone(), 3, Address::generate(&env), false, false).unwrap();
+        assert_eq!(
+            NepaBillingContract::can_upgrade_to(env.clone(), 3),
+            Err(Symbol::short("UNSAFE_UPGRADE"))
+        );
+
+        // Migration required but no script from the current version
+        VersionManager::register_version(env.clone(), admin.clone(), 4, Address::generate(&env), true, true).unwrap();
+        assert_eq!(
+            NepaBillingContract::can_upgrade_to(env.clone(), 4),
+            Err(Symbol::short("MIGRATION_NOT_FOUND"))
+        );
+
+        // Target not ahead of the current version
+        assert_eq!(
+            NepaBillingContract::can_upgrade_to(env.clone(), 1),
+            Err(Symbol::short("VER_LOWER"))
+        );
+
+        // Current version unknown to the version manager
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), Address::generate(&env), 5).unwrap();
+        VersionManager::register_version(env.clone(), admin.clone(), 6, Address::generate(&env), false, true).unwrap();
+        assert_eq!(
+            NepaBillingContract::can_upgrade_to(env.clone(), 6),
+            Err(Symbol::short("FROM_VERSION_NOT_FOUND"))
+        );
+    }
+
+    #[test]
+    fn test_migration_path_chain() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        DataMigration::initialize(env.clone(), admin.clone());
+
+        DataMigration::register_migration_script(env.clone(), admin.clone(), 1, 2, [1u8; 32], Symbol::short("V1_TO_V2")).unwrap();
+        DataMigration::register_migration_script(env.clone(), admin.clone(), 2, 3, [2u8; 32], Symbol::short("V2_TO_V3")).unwrap();
+
+        // Complete 1 -> 2 -> 3 chain, in execution order
+        let path = DataMigration::get_migration_path(env.clone(), 1, 3);
+        assert_eq!(path.len(), 2);
+        assert_eq!(path.get(0).unwrap().from_version, 1);
+        assert_eq!(path.get(0).unwrap().to_version, 2);
+        assert_eq!(path.get(1).unwrap().from_version, 2);
+        assert_eq!(path.get(1).unwrap().to_version, 3);
+
+        // Missing 3 -> 4 link breaks the chain to 5
+        DataMigration::register_migration_script(env.clone(), admin.clone(), 4, 5, [4u8; 32], Symbol::short("V4_TO_V5")).unwrap();
+        assert!(DataMigration::get_migration_path(env.clone(), 1, 5).is_empty());
+    }
+
+    #[test]
+    fn test_upgrade_version_must_increase() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+        let v2_implementation = Address::generate(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+
+        // Forward upgrade succeeds
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), v2_implementation.clone(), 2).unwrap();
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 2);
+
+        // Same or lower version is rejected through the normal path
+        let result = UpgradeProxy::upgrade(env.clone(), admin.clone(), Address::generate(&env), 2);
+        assert_eq!(result.unwrap_err(), Symbol::short("VER_LOWER"));
+        let result = UpgradeProxy::upgrade(env.clone(), admin.clone(), Address::generate(&env), 1);
+        assert_eq!(result.unwrap_err(), Symbol::short("VER_LOWER"));
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 2);
+        assert_eq!(UpgradeProxy::get_implementation(env.clone()), v2_implementation);
+    }
+
+    #[test]
+    fn test_rollback_to_earlier_version() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+        let v1_implementation = Address::generate(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), Address::generate(&env), 3).unwrap();
+
+        // Rollback must target an earlier version
+        let result = UpgradeProxy::rollback_to(env.clone(), admin.clone(), Address::generate(&env), 3);
+        assert_eq!(result.unwrap_err(), Symbol::short("NOT_RBACK"));
+
+        let result = UpgradeProxy::rollback_to(env.clone(), Address::generate(&env), v1_implementation.clone(), 1);
+        assert_eq!(result.unwrap_err(), Symbol::short("UNAUTHORIZED"));
+
+        UpgradeProxy::rollback_to(env.clone(), admin.clone(), v1_implementation.clone(), 1).unwrap();
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 1);
+        assert_eq!(UpgradeProxy::get_implementation(env.clone()), v1_implementation);
+    }
+
+    #[test]
+    fn test_rollback_undoes_last_upgrade() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+        let v2_implementation = Address::generate(&env);
+        let v3_implementation = Address::generate(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+
+        // Nothing to roll back to from the initial implementation
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), v2_implementation.clone(), 2).unwrap();
+        let result = UpgradeProxy::rollback(env.clone(), admin.clone());
+        assert_eq!(result.unwrap_err(), Symbol::short("NO_PREV"));
+
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), v3_implementation, 3).unwrap();
+
+        let result = UpgradeProxy::rollback(env.clone(), Address::generate(&env));
+        assert_eq!(result.unwrap_err(), Symbol::short("UNAUTHORIZED"));
+
+        UpgradeProxy::rollback(env.clone(), admin.clone()).unwrap();
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 2);
+        assert_eq!(UpgradeProxy::get_implementation(env.clone()), v2_implementation);
+
+        // Only one step back
+        let result = UpgradeProxy::rollback(env.clone(), admin.clone());
+        assert_eq!(result.unwrap_err(), Symbol::short("NO_PREV"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_upgrade_requires_admin_auth() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+
+        // Knowing the admin address isn't enough to install an implementation
+        env.set_auths(&[]);
+        let _ = UpgradeProxy::upgrade(env.clone(), admin, Address::generate(&env), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rollback_requires_admin_auth() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), Address::generate(&env), 2).unwrap();
+        UpgradeProxy::upgrade(env.clone(), admin.clone(), Address::generate(&env), 3).unwrap();
+
+        // Knowing the admin address isn't enough to downgrade
+        env.set_auths(&[]);
+        let _ = UpgradeProxy::rollback(env.clone(), admin);
+    }
+
+    #[test]
+    fn test_timelocked_upgrade() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+        let v2_implementation = Address::generate(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+        UpgradeProxy::set_timelock(env.clone(), admin.clone(), 86400).unwrap();
+
+        // Instant upgrades are off while a timelock is set
+        let result = UpgradeProxy::upgrade(env.clone(), admin.clone(), v2_implementation.clone(), 2);
+        assert_eq!(result.unwrap_err(), Symbol::short("TL_ACTIVE"));
+
+        UpgradeProxy::propose_upgrade(env.clone(), admin.clone(), v2_implementation.clone(), 2).unwrap();
+
+        // Too early
+        env.ledger().with_mut(|li| li.timestamp += 86399);
+        let result = UpgradeProxy::execute_upgrade(env.clone(), admin.clone());
+        assert_eq!(result.unwrap_err(), Symbol::short("TOO_EARLY"));
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 1);
+
+        env.ledger().with_mut(|li| li.timestamp += 1);
+        UpgradeProxy::execute_upgrade(env.clone(), admin.clone()).unwrap();
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 2);
+        assert_eq!(UpgradeProxy::get_implementation(env.clone()), v2_implementation);
+        assert!(UpgradeProxy::get_pending_upgrade(env.clone()).is_none());
+    }
+
+    #[test]
+    fn test_cancel_pending_upgrade() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+        UpgradeProxy::set_timelock(env.clone(), admin.clone(), 86400).unwrap();
+        UpgradeProxy::propose_upgrade(env.clone(), admin.clone(), Address::generate(&env), 2).unwrap();
+
+        let result = UpgradeProxy::cancel_upgrade(env.clone(), Address::generate(&env));
+        assert_eq!(result.unwrap_err(), Symbol::short("UNAUTHORIZED"));
+
+        UpgradeProxy::cancel_upgrade(env.clone(), admin.clone()).unwrap();
+        assert!(UpgradeProxy::get_pending_upgrade(env.clone()).is_none());
+
+        // Nothing left to execute, even after the delay
+        env.ledger().with_mut(|li| li.timestamp += 86400);
+        let result = UpgradeProxy::execute_upgrade(env.clone(), admin);
+        assert_eq!(result.unwrap_err(), Symbol::short("NO_PENDNG"));
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_timelock_changes_require_admin_auth() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        UpgradeProxy::initialize(env.clone(), admin.clone()).unwrap();
+        UpgradeProxy::set_timelock(env.clone(), admin.clone(), 86400).unwrap();
+
+        // Passing the admin address without its signature can't lift the timelock
+        env.set_auths(&[]);
+        let _ = UpgradeProxy::set_timelock(env.clone(), admin, 0);
+    }
+
+    #[test]
+    fn test_contract_upgrade_through_timelock() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+        let v2_implementation = Address::generate(&env);
+
+        setup_upgrade_systems(&env, &admin);
+        VersionManager::register_version(env.clone(), admin.clone(), 2, v2_implementation.clone(), true, true).unwrap();
+        DataMigration::register_migration_script(env.clone(), admin.clone(), 1, 2, [1u8; 32], Symbol::short("V1_TO_V2")).unwrap();
+        UpgradeProxy::set_timelock(env.clone(), admin.clone(), 86400).unwrap();
+
+        // The one-shot path is closed while a timelock is set
+        let result = NepaBillingContract::upgrade_contract(env.clone(), admin.clone(), v2_implementation.clone(), 2);
+        assert_eq!(result.unwrap_err(), Symbol::short("TL_ACTIVE"));
+
+        // Proposals run the same preflight
+        let result = NepaBillingContract::propose_contract_upgrade(env.clone(), admin.clone(), Address::generate(&env), 3);
+        assert_eq!(result.unwrap_err(), Symbol::short("TO_VERSION_NOT_FOUND"));
+
+        NepaBillingContract::propose_contract_upgrade(env.clone(), admin.clone(), v2_implementation.clone(), 2).unwrap();
+        let result = NepaBillingContract::execute_contract_upgrade(env.clone(), admin.clone());
+        assert_eq!(result.unwrap_err(), Symbol::short("TOO_EARLY"));
+
+        env.ledger().with_mut(|li| li.timestamp += 86400);
+        NepaBillingContract::execute_contract_upgrade(env.clone(), admin.clone()).unwrap();
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 2);
+        assert_eq!(UpgradeProxy::get_implementation(env.clone()), v2_implementation);
+        assert!(UpgradeProxy::get_pending_upgrade(env.clone()).is_none());
+    }
+
+    #[test]
+    fn test_execute_contract_upgrade_rechecks_preflight() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+
+        setup_upgrade_systems(&env, &admin);
+        VersionManager::register_version(env.clone(), admin.clone(), 2, Address::generate(&env), false, true).unwrap();
+        UpgradeProxy::set_timelock(env.clone(), admin.clone(), 86400).unwrap();
+
+        NepaBillingContract::propose_contract_upgrade(env.clone(), admin.clone(), Address::generate(&env), 2).unwrap();
+
+        // Deprecated while the proposal was waiting out the timelock
+        VersionManager::deprecate_version(env.clone(), admin.clone(), 2).unwrap();
+        env.ledger().with_mut(|li| li.timestamp += 86400);
+        let result = NepaBillingContract::execute_contract_upgrade(env.clone(), admin.clone());
+        assert_eq!(result.unwrap_err(), Symbol::short("VER_DEPR"));
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 1);
+    }
+
+    #[test]
+    fn test_upgrade_needs_threshold_of_approvals() {
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        let third = Address::generate(&env);
+        let v2_implementation = Address::generate(&env);
+
+        let approvers = soroban_sdk::vec![&env, first.clone(), second.clone(), third];
+        let result = UpgradeProxy::initialize_with_approvers(env.clone(), admin.clone(), approvers.clone(), 4);
+        assert_eq!(result.unwrap_err(), Symbol::short("BAD_THRES"));
+        UpgradeProxy::initialize_with_approvers(env.clone(), admin.clone(), approvers, 2).unwrap();
+
+        // A live proxy can't be re-initialized to swap approvers or lower the threshold
+        let intruder = Address::generate(&env);
+        let result = UpgradeProxy::initialize_with_approvers(
+            env.clone(),
+            intruder.clone(),
+            soroban_sdk::vec![&env, intruder],
+            1,
+        );
+        assert_eq!(result.unwrap_err(), Symbol::short("ALR_INIT"));
+        assert_eq!(UpgradeProxy::get_admin(env.clone()), admin);
+        assert_eq!(UpgradeProxy::get_required_approvals(env.clone()), 2);
+        assert_eq!(UpgradeProxy::get_approvers(env.clone()).len(), 3);
+
+        // The single admin key can't upgrade on its own
+        let result = UpgradeProxy::upgrade(env.clone(), admin.clone(), v2_implementation.clone(), 2);
+        assert_eq!(result.unwrap_err(), Symbol::short("NEED_APPR"));
+
+        UpgradeProxy::propose_upgrade(env.clone(), admin.clone(), v2_implementation.clone(), 2).unwrap();
+        let result = UpgradeProxy::approve_upgrade(env.clone(), Address::generate(&env));
+        assert_eq!(result.unwrap_err(), Symbol::short("NOT_APPR"));
+
+        // One approval, given twice, is still one
+        UpgradeProxy::approve_upgrade(env.clone(), first.clone()).unwrap();
+        UpgradeProxy::approve_upgrade(env.clone(), first).unwrap();
+        assert_eq!(UpgradeProxy::get_upgrade_approvals(env.clone()).len(), 1);
+        let result = UpgradeProxy::execute_upgrade(env.clone(), admin.clone());
+        assert_eq!(result.unwrap_err(), Symbol::short("FEW_APPRS"));
+
+        UpgradeProxy::approve_upgrade(env.clone(), second).unwrap();
+        UpgradeProxy::execute_upgrade(env.clone(), admin.clone()).unwrap();
+        assert_eq!(UpgradeProxy::get_version(env.clone()), 2);
+        assert_eq!(UpgradeProxy::get_implementation(env.clone()), v2_implementation);
+        assert_eq!(UpgradeProxy::get_upgrade_approvals(env.clone()).len(), 0);
+    }
+
+    #[test]
+    fn test_restore_reverts_multi_utility_registries() {
+        use crate::multi_utility::MultiUtilityManager;
+        use soroban_sdk::String;
+
+        let env = create_test_env();
+        let admin = create_test_admin(&env);
+        let provider_id = String::from_str(&env, "provider_001");
+
+        DataMigration::initialize(env.clone(), admin.clone());
+        MultiUtilityManager::initialize(env.clone(), admin.clone());
+        MultiUtilityManager::register_provider(
+            env.clone(),
+            admin.clone(),
+            provider_id.clone(),
+            String::from_str(&env, "Test Co"),
+            Address::generate(&env),
+            1, // Electricity
+            String::from_str(&env, "Lagos"),
+            String::from_str(&env, "LICENSE001"),
+            String::from_str(&env, "contact@test.com"),
+        ).unwrap();
+
+        let backup_id = DataMigration::backup_data(env.clone(), admin.clone()).unwrap();
+
+        MultiUtilityManager::update_provider_status(env.clone(), admin.clone(), provider_id.clone(), false).unwrap();
+        assert!(!MultiUtilityManager::get_provider(env.clone(), provider_id.clone()).unwrap().is_active);
+
+        DataMigration::restore_data(env.clone(), admin.clone(), backup_id).unwrap();
+        assert!(MultiUtilityManager::get_provider(env.clone(), provider_id).unwrap().is_active);
+
+        let result = DataMigration::restore_data(env.clone(), admin, Symbol::short("BACKUP_1"));
+        assert_eq!(result.unwrap_err(), Symbol::short("NO_BACKUP"));
+    }
 }
@@ -7,6 +7,7 @@ pub struct ContractVersion {
     pub deployment_timestamp: u64,
     pub migration_required: bool,
     pub backward_compatible: bool,
+    pub deprecated: bool,
 }
 
 #[contract]
@@ -53,6 +54,7 @@ impl VersionManager {
             deployment_timestamp: env.ledger().timestamp(),
             migration_required,
             backward_compatible,
+            deprecated: false,
         };
 
         // Get existing versions
@@ -138,6 +140,35 @@ impl VersionManager {
         Ok(true)
     }
 
+    /// Mark a version as deprecated so it is no longer an upgrade target
+    pub fn deprecate_version(env: Env, admin: Address, version: u32) -> Result<(), Symbol> {
+        // Verify admin
+        let current_admin = env.storage()
+            .instance()
+            .get::<Symbol, Address>(&Symbol::short("ADMIN"))
+            .unwrap();
+
+        if current_admin != admin {
+            return Err(Symbol::short("UNAUTHORIZED"));
+        }
+
+        let mut versions: Map<u32, ContractVersion> = env.storage()
+            .instance()
+            .get(&Symbol::short("VERSIONS"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut version_info = versions.get(version)
+            .ok_or(Symbol::short("NO_VER"))?;
+        version_info.deprecated = true;
+        versions.set(version, version_info);
+
+        env.storage()
+            .instance()
+            .set(&Symbol::short("VERSIONS"), &versions);
+
+        Ok(())
+    }
+
     /// List all versions
     pub fn list_versions(env: Env) -> Map<u32, ContractVersion> {
         env.storage()
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{Env, testutils::{Address as _, Ledger}, String};
+use soroban_sdk::{vec, Env, IntoVal, testutils::{Address as _, Events, Ledger}, String};
 
 #[test]
 fn test_registration() {
@@ -80,8 +80,279 @@ fn test_activity_tracking() {
 
     assert_eq!(client.get_activity_count(&user), 0);
 
-    client.log_activity(&user);
-    client.log_activity(&user);
+    client.log_activity(&user, &symbol_short!("LOGIN"));
+    client.log_activity(&user, &symbol_short!("PAYMENT"));
 
     assert_eq!(client.get_activity_count(&user), 2);
-}
\ No newline at end of file
+}
+#[test]
+#[should_panic(expected = "Cannot suspend last admin")]
+fn test_instance_admin_cannot_be_suspended() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let other_admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register(&other_admin, &String::from_str(&env, "profile"));
+    client.set_role(&admin, &other_admin, &UserRole::Admin);
+
+    // Even with another active admin, the instance admin is protected
+    client.suspend_user(&other_admin, &admin);
+}
+
+#[test]
+#[should_panic(expected = "Cannot suspend last admin")]
+fn test_last_role_admin_cannot_be_suspended() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let role_admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register(&role_admin, &String::from_str(&env, "profile"));
+    client.set_role(&admin, &role_admin, &UserRole::Admin);
+
+    // Instance admin gives up its role, leaving role_admin as the only role-admin
+    client.set_role(&admin, &admin, &UserRole::User);
+
+    client.suspend_user(&admin, &role_admin);
+}
+
+#[test]
+fn test_role_admin_can_be_suspended_when_others_remain() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let role_admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register(&role_admin, &String::from_str(&env, "profile"));
+    client.set_role(&admin, &role_admin, &UserRole::Admin);
+
+    client.suspend_user(&admin, &role_admin);
+    assert_eq!(client.is_active(&role_admin), false);
+}
+
+#[test]
+fn test_verify_users_batch_skips_unregistered() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register(&user1, &String::from_str(&env, "profile_1"));
+    client.register(&user2, &String::from_str(&env, "profile_2"));
+
+    let users = Vec::from_array(&env, [user1.clone(), stranger.clone(), user2.clone()]);
+    let skipped = client.verify_users_batch(&admin, &users);
+
+    assert_eq!(skipped, Vec::from_array(&env, [stranger]));
+    assert_eq!(client.get_profile(&user1).is_verified, true);
+    assert_eq!(client.get_profile(&user2).is_verified, true);
+}
+
+#[test]
+fn test_is_verified() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&admin);
+    assert_eq!(client.is_verified(&user), false);
+
+    client.register(&user, &String::from_str(&env, "profile"));
+    assert_eq!(client.is_verified(&user), false);
+
+    client.verify_user(&admin, &user);
+    assert_eq!(client.is_verified(&user), true);
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let first_choice = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&admin);
+
+    // A fresh proposal replaces one that was never accepted
+    client.propose_admin(&admin, &first_choice);
+    client.propose_admin(&admin, &new_admin);
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(client.get_role(&new_admin), UserRole::Admin);
+
+    // The new admin can act, and the old one is no longer protected as instance admin
+    client.register(&user, &String::from_str(&env, "profile"));
+    client.verify_user(&new_admin, &user);
+    assert_eq!(client.is_verified(&user), true);
+    client.suspend_user(&new_admin, &admin);
+    assert_eq!(client.is_active(&admin), false);
+}
+
+#[test]
+#[should_panic(expected = "Not the pending admin")]
+fn test_accept_admin_rejects_unproposed_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let intruder = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.propose_admin(&admin, &new_admin);
+
+    client.accept_admin(&intruder);
+}
+
+#[test]
+fn test_lifecycle_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let profile_hash = String::from_str(&env, "profile");
+
+    client.initialize(&admin);
+
+    client.register(&user, &profile_hash);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("USER_REG"), user.clone()).into_val(&env),
+                profile_hash.into_val(&env),
+            ),
+        ]
+    );
+
+    client.set_role(&admin, &user, &UserRole::UtilityProvider);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("ROLE_SET"), user.clone()).into_val(&env),
+                UserRole::UtilityProvider.into_val(&env),
+            ),
+        ]
+    );
+
+    client.suspend_user(&admin, &user);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("SUSPENDED"), user.clone()).into_val(&env),
+                admin.into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_adjust_reputation_clamps_to_range() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register(&user, &String::from_str(&env, "profile"));
+
+    client.adjust_reputation(&admin, &user, &40);
+    assert_eq!(client.get_reputation(&user), 40);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("REP_ADJ"), user.clone()).into_val(&env),
+                (0u32, 40u32).into_val(&env),
+            ),
+        ]
+    );
+
+    // Penalties floor at zero
+    client.adjust_reputation(&admin, &user, &-100);
+    assert_eq!(client.get_reputation(&user), 0);
+
+    // Rewards stop at the ceiling
+    client.adjust_reputation(&admin, &user, &(MAX_REPUTATION as i32 - 10));
+    client.adjust_reputation(&admin, &user, &25);
+    assert_eq!(client.get_reputation(&user), MAX_REPUTATION);
+}
+
+#[test]
+fn test_recent_activity_keeps_latest_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, UserManagement);
+    let client = UserManagementClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.register(&user, &String::from_str(&env, "profile"));
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    client.log_activity(&user, &symbol_short!("LOGIN"));
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    client.log_activity(&user, &symbol_short!("PAYMENT"));
+
+    assert_eq!(client.get_activity_count(&user), 2);
+    assert_eq!(
+        client.get_recent_activity(&user),
+        vec![&env, (symbol_short!("LOGIN"), 100u64), (symbol_short!("PAYMENT"), 200u64)]
+    );
+
+    // Past the limit the oldest entries drop off while the counter keeps counting
+    for i in 0..MAX_RECENT_ACTIVITY {
+        env.ledger().with_mut(|li| li.timestamp = 1000 + i as u64);
+        client.log_activity(&user, &symbol_short!("READING"));
+    }
+    let recent = client.get_recent_activity(&user);
+    assert_eq!(client.get_activity_count(&user), 2 + MAX_RECENT_ACTIVITY as u64);
+    assert_eq!(recent.len(), MAX_RECENT_ACTIVITY);
+    assert_eq!(recent.first().unwrap(), (symbol_short!("READING"), 1000u64));
+    assert_eq!(recent.last().unwrap(), (symbol_short!("READING"), 1000 + MAX_RECENT_ACTIVITY as u64 - 1));
+}